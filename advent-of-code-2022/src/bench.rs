@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::hint::black_box;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, ParseError};
+use crate::solution::{Day, Part};
+
+// What gets measured on its own, reading the input never is
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse => f.pad("parse"),
+            Self::Solve(part) => f.pad(&format!("part {}", part)),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct StageParseError(String);
+
+impl std::fmt::Display for StageParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid stage {:?}, expected parse, part 1 or part 2",
+            self.0
+        )
+    }
+}
+
+impl FromStr for Stage {
+    type Err = StageParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("part ") {
+            Some(part) => part
+                .parse()
+                .map(Stage::Solve)
+                .map_err(|_| StageParseError(s.to_string())),
+            None if s == "parse" => Ok(Stage::Parse),
+            None => Err(StageParseError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let count = sorted.len();
+        let min = *sorted.first()?;
+        let median = match count % 2 {
+            0 => (sorted[count / 2 - 1] + sorted[count / 2]) / 2,
+            _ => sorted[count / 2],
+        };
+        let mean = sorted.iter().sum::<Duration>() / count as u32;
+        let variance = sorted
+            .iter()
+            .map(|sample| (sample.as_secs_f64() - mean.as_secs_f64()).powi(2))
+            .sum::<f64>()
+            / count as f64;
+
+        Some(Stats {
+            min,
+            median,
+            mean,
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        })
+    }
+}
+
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {:>10.2?}  median {:>10.2?}  mean {:>10.2?}  stddev {:>10.2?}",
+            self.min, self.median, self.mean, self.stddev
+        )
+    }
+}
+
+// Runs f warmup times untimed, then runs times, keeping the last result
+fn measure<R, F>(warmup: usize, runs: usize, mut f: F) -> Result<(Stats, R), Error>
+where
+    F: FnMut() -> Result<R, Error>,
+{
+    for _ in 0..warmup {
+        black_box(f()?);
+    }
+
+    let mut samples = Vec::with_capacity(runs);
+    let mut last = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let result = black_box(f()?);
+        samples.push(start.elapsed());
+        last = Some(result);
+    }
+
+    // Samples and result are only missing without any run, which the CLI refuses
+    let stats = Stats::from_samples(&samples).expect("at least one run");
+    Ok((stats, last.expect("at least one run")))
+}
+
+pub fn bench_day(
+    day: &Day,
+    input: &str,
+    parts: &[Part],
+    warmup: usize,
+    runs: usize,
+) -> Result<Vec<(Stage, Stats)>, Error> {
+    let (stats, parsed) = measure(warmup, runs, || day.parse(input))?;
+    let mut stages = vec![(Stage::Parse, stats)];
+
+    for part in parts {
+        let (stats, _) = measure(warmup, runs, || parsed.solve(*part))?;
+        stages.push((Stage::Solve(*part), stats));
+    }
+
+    Ok(stages)
+}
+
+// Median time of every stage, saved as tab separated lines:
+// day, stage and nanoseconds
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Baseline {
+    medians: BTreeMap<(u8, Stage), Duration>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum BaselineLineParseError {
+    InvalidDay(String),
+    InvalidDuration(String),
+    InvalidStage(StageParseError),
+    MissingField,
+}
+
+impl std::fmt::Display for BaselineLineParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidDay(s) => write!(f, "Invalid day {:?}", s),
+            Self::InvalidDuration(s) => write!(f, "Invalid nanoseconds {:?}", s),
+            Self::InvalidStage(error) => write!(f, "{}", error),
+            Self::MissingField => write!(f, "Expected day, stage and nanoseconds"),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Result<(u8, Stage, Duration), BaselineLineParseError> {
+    match line.split('\t').collect::<Vec<&str>>()[..] {
+        [day, stage, nanos] => Ok((
+            day.parse()
+                .map_err(|_| BaselineLineParseError::InvalidDay(day.to_string()))?,
+            stage
+                .parse()
+                .map_err(BaselineLineParseError::InvalidStage)?,
+            nanos
+                .parse()
+                .map(Duration::from_nanos)
+                .map_err(|_| BaselineLineParseError::InvalidDuration(nanos.to_string()))?,
+        )),
+        _ => Err(BaselineLineParseError::MissingField),
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut baseline = Baseline::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let (day, stage, median) = parse_line(line)
+                .map_err(|error| ParseError::new(error).at_line(index + 1, line))?;
+            baseline.insert(day, stage, median);
+        }
+
+        Ok(baseline)
+    }
+}
+
+impl std::fmt::Display for Baseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for ((day, stage), median) in &self.medians {
+            writeln!(f, "{}\t{}\t{}", day, stage, median.as_nanos())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum BaselineError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "Unable to access the baseline"),
+            Self::Parse(_) => write!(f, "Unable to parse the baseline"),
+        }
+    }
+}
+
+impl std::error::Error for BaselineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl Baseline {
+    // Unlike the ledger, a baseline asked for must exist
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        fs::read_to_string(path)
+            .map_err(BaselineError::Io)?
+            .parse()
+            .map_err(BaselineError::Parse)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), BaselineError> {
+        fs::write(path, self.to_string()).map_err(BaselineError::Io)
+    }
+
+    pub fn insert(&mut self, day: u8, stage: Stage, median: Duration) {
+        self.medians.insert((day, stage), median);
+    }
+
+    // Relative change of the median, in percent, when the stage has a baseline
+    pub fn change(&self, day: u8, stage: Stage, median: Duration) -> Option<f64> {
+        self.medians
+            .get(&(day, stage))
+            .filter(|baseline| !baseline.is_zero())
+            .map(|baseline| (median.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution;
+
+    #[test]
+    fn test_stage_from_str() {
+        assert_eq!("parse".parse::<Stage>(), Ok(Stage::Parse));
+        assert_eq!("part 2".parse::<Stage>(), Ok(Stage::Solve(Part::Two)));
+        assert_eq!(
+            "part 3".parse::<Stage>(),
+            Err(StageParseError(String::from("part 3")))
+        );
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let samples = [4, 1, 3, 2].map(Duration::from_millis);
+        assert_eq!(
+            Stats::from_samples(&samples),
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_micros(2500),
+                mean: Duration::from_micros(2500),
+                stddev: Duration::from_secs_f64(1.25f64.sqrt() / 1000.0),
+            })
+        );
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let day = solution::find(1).unwrap();
+        let input = solution::read_fixture(1);
+        let stages = bench_day(day, &input, &[Part::Two], 1, 3).unwrap();
+        let stages: Vec<Stage> = stages.into_iter().map(|(stage, _)| stage).collect();
+        assert_eq!(stages, vec![Stage::Parse, Stage::Solve(Part::Two)]);
+    }
+
+    #[test]
+    fn test_bench_day_err() {
+        let day = solution::find(1).unwrap();
+        assert!(bench_day(day, "BOOM", &Part::ALL, 1, 3).is_err());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let mut baseline = Baseline::default();
+        baseline.insert(11, Stage::Solve(Part::Two), Duration::from_millis(250));
+        baseline.insert(1, Stage::Parse, Duration::from_nanos(1200));
+        let content = baseline.to_string();
+
+        assert_eq!(content, "1\tparse\t1200\n11\tpart 2\t250000000\n");
+        assert_eq!(content.parse::<Baseline>(), Ok(baseline));
+    }
+
+    #[test]
+    fn test_baseline_from_str_err() {
+        let result = "1\tparse\tfast".parse::<Baseline>();
+        assert_eq!(
+            result,
+            Err(
+                ParseError::new(BaselineLineParseError::InvalidDuration(String::from(
+                    "fast"
+                )))
+                .at_line(1, "1\tparse\tfast")
+            )
+        );
+    }
+
+    #[test]
+    fn test_baseline_change() {
+        let mut baseline = Baseline::default();
+        baseline.insert(11, Stage::Solve(Part::Two), Duration::from_millis(200));
+        let change = baseline.change(11, Stage::Solve(Part::Two), Duration::from_millis(250));
+        assert!((change.unwrap() - 25.0).abs() < 1e-9);
+        assert_eq!(
+            baseline.change(11, Stage::Parse, Duration::from_millis(1)),
+            None
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::error::ParseError;
+
+// Pages adventofcode.com serves instead of an input
+const ERROR_PAGES: [&str; 4] = [
+    "Puzzle inputs differ by user",
+    "Please don't repeatedly request this endpoint before it unlocks",
+    "<!DOCTYPE html>",
+    "500 Internal Server Error",
+];
+
+// FNV-1a, stable across Rust versions unlike the std hashers
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// Stored next to every cached input, as "key = value" lines
+#[derive(Debug, Eq, PartialEq)]
+pub struct Metadata {
+    pub fetched_at: u64,
+    pub status: u16,
+    pub hash: u64,
+    pub year: u16,
+}
+
+impl std::fmt::Display for Metadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "fetched_at = {}", self.fetched_at)?;
+        writeln!(f, "status = {}", self.status)?;
+        writeln!(f, "hash = {:016x}", self.hash)?;
+        writeln!(f, "year = {}", self.year)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum MetadataParseError {
+    InvalidValue(String),
+    MissingKey(&'static str),
+    UnknownKey(String),
+}
+
+impl std::fmt::Display for MetadataParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidValue(s) => write!(f, "Invalid value {:?}", s),
+            Self::MissingKey(key) => write!(f, "Missing {}", key),
+            Self::UnknownKey(s) => write!(f, "Unknown key {:?}", s),
+        }
+    }
+}
+
+impl FromStr for Metadata {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut fetched_at, mut status, mut hash, mut year) = (None, None, None, None);
+
+        for (index, line) in s.lines().enumerate() {
+            let at_line = |error| ParseError::new(error).at_line(index + 1, line);
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| at_line(MetadataParseError::InvalidValue(line.to_string())))?;
+            let invalid = || at_line(MetadataParseError::InvalidValue(value.to_string()));
+
+            match key {
+                "fetched_at" => fetched_at = Some(value.parse().map_err(|_| invalid())?),
+                "status" => status = Some(value.parse().map_err(|_| invalid())?),
+                "hash" => hash = Some(u64::from_str_radix(value, 16).map_err(|_| invalid())?),
+                "year" => year = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(at_line(MetadataParseError::UnknownKey(key.to_string()))),
+            }
+        }
+
+        let missing = |key| ParseError::new(MetadataParseError::MissingKey(key));
+        Ok(Metadata {
+            fetched_at: fetched_at.ok_or_else(|| missing("fetched_at"))?,
+            status: status.ok_or_else(|| missing("status"))?,
+            hash: hash.ok_or_else(|| missing("hash"))?,
+            year: year.ok_or_else(|| missing("year"))?,
+        })
+    }
+}
+
+// Why a cached input can't be trusted
+#[derive(Debug, Eq, PartialEq)]
+pub enum Poison {
+    BadStatus(u16),
+    ErrorPage,
+    HashMismatch,
+    InvalidMetadata(ParseError),
+    MissingMetadata,
+    WrongYear(u16),
+}
+
+impl std::fmt::Display for Poison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadStatus(status) => write!(f, "Fetched with HTTP status {}", status),
+            Self::ErrorPage => write!(f, "Looks like an error page"),
+            Self::HashMismatch => write!(f, "Content doesn't match its hash"),
+            Self::InvalidMetadata(_) => write!(f, "Invalid metadata"),
+            Self::MissingMetadata => write!(f, "No metadata"),
+            Self::WrongYear(year) => write!(f, "Fetched for year {}", year),
+        }
+    }
+}
+
+impl std::error::Error for Poison {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidMetadata(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+// What the cache had for a day
+#[derive(Debug, Eq, PartialEq)]
+pub enum Cached {
+    Evicted(Poison),
+    Hit(String),
+    Miss,
+}
+
+fn check_body(body: &str) -> Result<(), Poison> {
+    match body.is_empty() || ERROR_PAGES.iter().any(|page| body.contains(page)) {
+        true => Err(Poison::ErrorPage),
+        false => Ok(()),
+    }
+}
+
+pub fn check_response(status: u16, body: &str) -> Result<(), Poison> {
+    if status != 200 {
+        return Err(Poison::BadStatus(status));
+    }
+
+    check_body(body)
+}
+
+// Inputs of one year, each "day_N" with its "day_N.meta"
+pub struct Cache {
+    dir: PathBuf,
+    year: u16,
+}
+
+impl Cache {
+    pub fn new(config: &Config) -> Self {
+        Cache {
+            dir: config.year_dir(),
+            year: config.year,
+        }
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day_{}", day))
+    }
+
+    fn metadata_path(&self, day: u8) -> PathBuf {
+        self.dir.join(format!("day_{}.meta", day))
+    }
+
+    fn check(&self, body: &str, metadata: Option<String>) -> Result<(), Poison> {
+        let metadata = metadata
+            .ok_or(Poison::MissingMetadata)?
+            .parse::<Metadata>()
+            .map_err(Poison::InvalidMetadata)?;
+
+        if metadata.status != 200 {
+            return Err(Poison::BadStatus(metadata.status));
+        }
+        if metadata.year != self.year {
+            return Err(Poison::WrongYear(metadata.year));
+        }
+        if metadata.hash != fnv1a(body.as_bytes()) {
+            return Err(Poison::HashMismatch);
+        }
+
+        check_body(body)
+    }
+
+    // Poisoned entries are evicted, and then missing like they were never cached
+    pub fn get(&self, day: u8) -> io::Result<Cached> {
+        let body = match fs::read_to_string(self.input_path(day)) {
+            Ok(body) => body,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Cached::Miss),
+            Err(error) => return Err(error),
+        };
+        let metadata = fs::read_to_string(self.metadata_path(day)).ok();
+
+        match self.check(&body, metadata) {
+            Ok(()) => Ok(Cached::Hit(body)),
+            Err(poison) => {
+                self.evict(day)?;
+                Ok(Cached::Evicted(poison))
+            }
+        }
+    }
+
+    // Only inputs served with a 200 are worth keeping, see check_response
+    pub fn put(&self, day: u8, body: &str) -> io::Result<()> {
+        let metadata = Metadata {
+            fetched_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            status: 200,
+            hash: fnv1a(body.as_bytes()),
+            year: self.year,
+        };
+
+        // The metadata comes last, a half written entry is then seen as poisoned
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.input_path(day), body)?;
+        fs::write(self.metadata_path(day), metadata.to_string())
+    }
+
+    pub fn evict(&self, day: u8) -> io::Result<()> {
+        for path in [self.input_path(day), self.metadata_path(day)] {
+            match fs::remove_file(path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache(name: &str) -> Cache {
+        Cache {
+            dir: std::env::temp_dir().join(format!("aoc-cache-{}-{}", name, std::process::id())),
+            year: 2022,
+        }
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn test_metadata_round_trip() {
+        let metadata = Metadata {
+            fetched_at: 1669870800,
+            status: 200,
+            hash: 0xaf63dc4c8601ec8c,
+            year: 2022,
+        };
+        assert_eq!(metadata.to_string().parse::<Metadata>(), Ok(metadata));
+    }
+
+    #[test]
+    fn test_metadata_from_str_missing_key_err() {
+        let result = "status = 200\nyear = 2022".parse::<Metadata>();
+        assert_eq!(
+            result,
+            Err(ParseError::new(MetadataParseError::MissingKey(
+                "fetched_at"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_cache_put_and_get() {
+        let cache = temp_cache("get");
+        cache.put(1, "1000\n2000\n\n3000\n").unwrap();
+        let result = cache.get(1);
+        fs::remove_dir_all(&cache.dir).unwrap();
+
+        assert_eq!(
+            result.unwrap(),
+            Cached::Hit(String::from("1000\n2000\n\n3000\n"))
+        );
+    }
+
+    #[test]
+    fn test_check_response_bad_status_err() {
+        assert_eq!(check_response(204, "1\n"), Err(Poison::BadStatus(204)));
+    }
+
+    #[test]
+    fn test_check_response_error_page_err() {
+        let body = "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n";
+        assert_eq!(check_response(200, body), Err(Poison::ErrorPage));
+        assert_eq!(check_response(200, ""), Err(Poison::ErrorPage));
+    }
+
+    #[test]
+    fn test_cache_get_evicts_tampered_entry() {
+        let cache = temp_cache("tampered");
+        cache.put(2, "A Y\nB X\n").unwrap();
+        fs::write(cache.input_path(2), "A Y\n").unwrap();
+        let result = cache.get(2);
+        let evicted = !cache.input_path(2).exists() && !cache.metadata_path(2).exists();
+        let again = cache.get(2);
+        fs::remove_dir_all(&cache.dir).unwrap();
+
+        assert_eq!(result.unwrap(), Cached::Evicted(Poison::HashMismatch));
+        assert_eq!(again.unwrap(), Cached::Miss);
+        assert!(evicted);
+    }
+
+    #[test]
+    fn test_cache_get_evicts_entry_without_metadata() {
+        let cache = temp_cache("legacy");
+        fs::create_dir_all(&cache.dir).unwrap();
+        fs::write(cache.input_path(3), "vJrwpWtwJgWrhcsFMMfFFhFp\n").unwrap();
+        let result = cache.get(3);
+        let evicted = !cache.input_path(3).exists();
+        fs::remove_dir_all(&cache.dir).unwrap();
+
+        assert_eq!(result.unwrap(), Cached::Evicted(Poison::MissingMetadata));
+        assert!(evicted);
+    }
+
+    #[test]
+    fn test_cache_check_wrong_year() {
+        let cache = temp_cache("year");
+        let metadata = Metadata {
+            fetched_at: 0,
+            status: 200,
+            hash: fnv1a(b"1\n"),
+            year: 2021,
+        };
+        assert_eq!(
+            cache.check("1\n", Some(metadata.to_string())),
+            Err(Poison::WrongYear(2021))
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, SolutionError};
+use crate::guard::Panic;
+use crate::solution::{Day, Part};
+
+thread_local! {
+    static TOKEN: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+// Solvers can check it in long loops and bail out once the runner gave up on them,
+// a day that never checks keeps its thread busy until the process exits
+pub fn cancelled() -> bool {
+    TOKEN.with(|token| {
+        token
+            .borrow()
+            .as_ref()
+            .is_some_and(|token| token.load(Ordering::Relaxed))
+    })
+}
+
+// Parsing counts as a stage of its own, limited like a part
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Limits {
+    pub day: Option<Duration>,
+    pub part: Option<Duration>,
+}
+
+enum Event {
+    Stage(Option<Part>),
+    Done(Result<Vec<(Part, String)>, Error>),
+}
+
+fn solve_parts<F>(
+    day: &Day,
+    input: &str,
+    parts: &[Part],
+    mut on_stage: F,
+) -> Result<Vec<(Part, String)>, Error>
+where
+    F: FnMut(Option<Part>),
+{
+    on_stage(None);
+    day.parse(input).and_then(|parsed| {
+        parts
+            .iter()
+            .map(|part| {
+                on_stage(Some(*part));
+                Ok((*part, parsed.solve(*part)?))
+            })
+            .collect()
+    })
+}
+
+// Solves the parts on a thread of their own, so that the caller can stop waiting
+pub fn solve_within(
+    day: &'static Day,
+    input: String,
+    parts: &[Part],
+    limits: Limits,
+) -> Result<Vec<(Part, String)>, Error> {
+    if limits == Limits::default() {
+        return solve_parts(day, &input, parts, |_| {});
+    }
+
+    let start = Instant::now();
+    let token = Arc::new(AtomicBool::new(false));
+    let (sender, receiver) = mpsc::channel();
+    let worker_token = Arc::clone(&token);
+    let worker_parts = parts.to_vec();
+    thread::spawn(move || {
+        TOKEN.with(|token| *token.borrow_mut() = Some(worker_token));
+        let result = solve_parts(day, &input, &worker_parts, |stage| {
+            let _ = sender.send(Event::Stage(stage));
+        });
+        let _ = sender.send(Event::Done(result));
+    });
+
+    let (mut stage, mut stage_start) = (None, start);
+    loop {
+        let deadline = [
+            limits.day.map(|limit| start + limit),
+            limits.part.map(|limit| stage_start + limit),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        // One of the limits is always set
+        let timeout = deadline
+            .unwrap_or(start)
+            .saturating_duration_since(Instant::now());
+
+        match receiver.recv_timeout(timeout) {
+            Ok(Event::Stage(next)) => (stage, stage_start) = (next, Instant::now()),
+            Ok(Event::Done(result)) => return result,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                token.store(true, Ordering::Relaxed);
+                return Err(Error::TimedOut {
+                    day: day.number,
+                    part: stage,
+                    elapsed: start.elapsed(),
+                });
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(Error::Solution {
+                    day: day.number,
+                    part: stage,
+                    source: SolutionError::Panic(Panic {
+                        message: String::from("Solver thread exited without an answer"),
+                        location: None,
+                    }),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{find, read_fixture};
+
+    #[test]
+    fn test_cancelled_outside_worker() {
+        assert!(!cancelled());
+    }
+
+    #[test]
+    fn test_solve_within_limits() {
+        let limits = Limits {
+            day: Some(Duration::from_secs(60)),
+            part: Some(Duration::from_secs(30)),
+        };
+        let result = solve_within(find(1).unwrap(), read_fixture(1), &Part::ALL, limits);
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                (Part::One, String::from("24000")),
+                (Part::Two, String::from("45000"))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_solve_within_part_timeout() {
+        let limits = Limits {
+            day: None,
+            part: Some(Duration::ZERO),
+        };
+        let result = solve_within(find(11).unwrap(), read_fixture(11), &[Part::Two], limits);
+        assert!(matches!(result, Err(Error::TimedOut { day: 11, .. })));
+    }
+
+    #[test]
+    fn test_cancelled_in_worker() {
+        let token = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let worker_token = Arc::clone(&token);
+        let worker = thread::spawn(move || {
+            TOKEN.with(|token| *token.borrow_mut() = Some(worker_token));
+            sender.send(()).unwrap();
+            let mut rounds = 0u64;
+            while !cancelled() {
+                rounds += 1;
+            }
+            rounds
+        });
+
+        receiver.recv().unwrap();
+        token.store(true, Ordering::Relaxed);
+        assert!(worker.join().is_ok());
+    }
+}
@@ -0,0 +1,965 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::cancel::Limits;
+use crate::report::Format;
+use crate::solution::Part;
+
+pub const USAGE: &str = "Usage:
+    advent-of-code-2022 [run] [DAYS...] [--part 1|2] [--all] [--input PATH | --example] [--wait] [--jobs N] [--format FORMAT]
+        [--timeout S] [--part-timeout S] [SETTINGS...]
+    advent-of-code-2022 bench [DAYS...] [--part 1|2] [--input PATH | --example] [BENCH...] [SETTINGS...]
+    advent-of-code-2022 submit DAY PART [--input PATH] [SETTINGS...]
+    advent-of-code-2022 new DAY [--fetch] [SETTINGS...]
+    advent-of-code-2022 example DAY [--force] [--show] [SETTINGS...]
+    advent-of-code-2022 render DAY [--input PATH | --example] [--image PATH] [SETTINGS...]
+    advent-of-code-2022 help
+
+Days:
+    7            a single day
+    3..9         every day from 3 to 9, inclusive (3..=9 works too)
+    --all        every available day (default when no days are given)
+
+Options:
+    -p, --part   solve only the given part
+    -i, --input  read the input from PATH instead of adventofcode.com,
+                 or from stdin when PATH is -; needs a single day
+    -e, --example
+                 solve the example in fixtures/day_N and check the answers
+                 against fixtures/day_N.expected when it exists
+    -w, --wait   wait for puzzles still locked instead of failing, they unlock
+                 at midnight US/Eastern from December 1st to 25th
+    -j, --jobs   solve up to N days at once, 1 by default; answers are still
+                 printed in order, each day timed on its own
+    -t, --timeout S
+                 give up on a day after S seconds, and carry on with the next
+    --part-timeout S
+                 give up on a day once parsing or a part takes S seconds
+    -f, --format text (default), json for a single document once every day
+                 is done, or ndjson for a line per day as soon as it is done
+
+Bench:
+    Times parsing and each part separately, reading the input beforehand
+    -n, --runs N       timed runs of each, 10 by default
+    --warmup N         untimed runs before them, 3 by default
+    --baseline PATH    compare the medians with those saved in PATH
+    --threshold PCT    flag medians slower than the baseline by more than
+                       PCT percent as regressions, 10 by default
+    --save PATH        save the medians to PATH, as a future baseline
+
+Submit:
+    Solves the given part of a day and submits the answer to adventofcode.com,
+    using the session cookie from the token source. Outcomes are logged to
+    the cache directory, and guesses already known to be wrong are refused
+
+New:
+    Adds a day to solve: src/day_N.rs from a template, with its tests, an
+    empty fixtures/day_N, and the day in src/main.rs and the registry
+    --fetch            fill the fixtures like the example command does
+
+Example:
+    Downloads the puzzle description and writes its example to fixtures/day_N,
+    and the answers it gives to fixtures/day_N.expected; part 2 only shows
+    once part 1 is solved
+    --force            overwrite the fixtures when they exist
+    --show             print the description as text
+
+Render:
+    Draws what a day makes of its input, for the days that can, like days 8
+    and 12
+    --image PATH       also save it as an image to PATH, PNG when it ends in .png
+                       and PPM otherwise
+
+Settings:
+    --config PATH      config file, $AOC_CONFIG, or else
+                       $XDG_CONFIG_HOME/advent-of-code/config when it exists
+    --base-url URL     $AOC_BASE_URL, https://adventofcode.com by default
+    --year YEAR        $AOC_YEAR, 2022 by default
+    --cache-dir DIR    $AOC_CACHE_DIR, $XDG_CACHE_HOME/advent-of-code by default
+    --token-env VAR    read the session cookie from $VAR, $AOC_TOKEN by default
+    --token-file PATH  read the session cookie from PATH, $AOC_TOKEN_FILE
+    --user-agent NAME  $AOC_USER_AGENT, advent-of-code-2022/VERSION by default
+    --contact EMAIL    $AOC_CONTACT, added to the User-Agent so that
+                       adventofcode.com can reach out
+    --min-interval S   $AOC_MIN_INTERVAL, seconds between requests, 3 by default
+    --retries N        $AOC_RETRIES, retries on server errors and timeouts, 3 by
+                       default
+
+    The config file holds one \"setting = value\" per line, named like the
+    flags with underscores: base_url, year, cache_dir, token_env, token_file,
+    user_agent, contact, min_interval, retries.
+    Flags override environment variables, which override the config file";
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum ArgsParseError {
+    ConflictingOptions(String, String),
+    ExistingDay(u8),
+    InvalidDay(String),
+    InvalidFormat(String),
+    InvalidNumber(String, String),
+    InvalidPart(String),
+    InputForManyDays,
+    MissingDay,
+    MissingPart,
+    MissingValue(String),
+    UnavailableDay(u8),
+    UnknownCommand(String),
+    UnknownOption(String),
+}
+
+impl std::fmt::Display for ArgsParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ConflictingOptions(a, b) => write!(f, "{} and {} can't be used together", a, b),
+            Self::ExistingDay(day) => write!(f, "Day {} already exists", day),
+            Self::InvalidDay(s) => write!(f, "Invalid day or day range: {}", s),
+            Self::InvalidFormat(s) => {
+                write!(f, "Invalid format: {}, expected text, json or ndjson", s)
+            }
+            Self::InvalidNumber(option, s) => write!(f, "Invalid number for {}: {}", option, s),
+            Self::InvalidPart(s) => write!(f, "Invalid part: {}, expected 1 or 2", s),
+            Self::InputForManyDays => write!(f, "--input needs exactly one day"),
+            Self::MissingDay => write!(f, "Missing day"),
+            Self::MissingPart => write!(f, "Missing part to submit"),
+            Self::MissingValue(s) => write!(f, "Missing value for {}", s),
+            Self::UnavailableDay(day) => write!(f, "Day {} is not solved yet", day),
+            Self::UnknownCommand(s) => write!(f, "Unknown command: {}", s),
+            Self::UnknownOption(s) => write!(f, "Unknown option: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for ArgsParseError {}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    pub days: Vec<u8>,
+    pub part: Option<Part>,
+    pub input: Option<PathBuf>,
+    pub example: bool,
+    pub wait: bool,
+    pub jobs: usize,
+    pub format: Format,
+    pub limits: Limits,
+    pub settings: Vec<(String, String)>,
+}
+
+impl RunOptions {
+    pub fn includes(&self, part: Part) -> bool {
+        self.part.is_none_or(|selected| selected == part)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SubmitOptions {
+    pub day: u8,
+    pub part: Part,
+    pub input: Option<PathBuf>,
+    pub settings: Vec<(String, String)>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RenderOptions {
+    pub day: u8,
+    pub input: Option<PathBuf>,
+    pub example: bool,
+    pub image: Option<PathBuf>,
+    pub settings: Vec<(String, String)>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ExampleOptions {
+    pub day: u8,
+    pub force: bool,
+    pub show: bool,
+    pub settings: Vec<(String, String)>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct NewOptions {
+    pub day: u8,
+    pub fetch: bool,
+    pub settings: Vec<(String, String)>,
+}
+
+// Days, part and input are picked like for a run, each day runs on its own
+#[derive(Debug, Eq, PartialEq)]
+pub struct BenchOptions {
+    pub run: RunOptions,
+    pub runs: usize,
+    pub warmup: usize,
+    // Slowdown of the median, in percent, flagged as a regression
+    pub threshold: usize,
+    pub baseline: Option<PathBuf>,
+    pub save: Option<PathBuf>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Bench(BenchOptions),
+    Example(ExampleOptions),
+    Help,
+    New(NewOptions),
+    Render(RenderOptions),
+    Run(RunOptions),
+    Submit(SubmitOptions),
+}
+
+fn parse_days(s: &str) -> Result<Vec<u8>, ArgsParseError> {
+    let parse_day = |x: &str| {
+        x.parse::<u8>()
+            .map_err(|_| ArgsParseError::InvalidDay(s.to_string()))
+    };
+
+    let (first, last) = match s.split_once("..") {
+        Some((first, last)) => {
+            let last = last.strip_prefix('=').unwrap_or(last);
+            (parse_day(first)?, parse_day(last)?)
+        }
+        None => (parse_day(s)?, parse_day(s)?),
+    };
+
+    if first > last {
+        return Err(ArgsParseError::InvalidDay(s.to_string()));
+    }
+
+    Ok((first..=last).collect())
+}
+
+const SETTINGS: [&str; 10] = [
+    "--config",
+    "--base-url",
+    "--year",
+    "--cache-dir",
+    "--token-env",
+    "--token-file",
+    "--user-agent",
+    "--contact",
+    "--min-interval",
+    "--retries",
+];
+
+// Settings are checked once the configuration is loaded
+fn parse_setting<I>(arg: String, args: &mut I) -> Result<(String, String), ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let name = arg.trim_start_matches('-').replace('-', "_");
+    let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+    Ok((name, value))
+}
+
+fn parse_count<I>(arg: String, args: &mut I, min: usize) -> Result<usize, ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let value = args
+        .next()
+        .ok_or(ArgsParseError::MissingValue(arg.clone()))?;
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|count| *count >= min)
+        .ok_or(ArgsParseError::InvalidNumber(arg, value))
+}
+
+fn parse_seconds<I>(arg: String, args: &mut I) -> Result<Duration, ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let value = args
+        .next()
+        .ok_or(ArgsParseError::MissingValue(arg.clone()))?;
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or(ArgsParseError::InvalidNumber(arg, value))
+}
+
+fn parse_submit<I>(mut args: I, available: &[u8]) -> Result<Command, ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut settings = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                input = Some(PathBuf::from(value));
+            }
+            x if SETTINGS.contains(&x) => settings.push(parse_setting(arg, &mut args)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            x if x.starts_with('-') => return Err(ArgsParseError::UnknownOption(arg)),
+            x if day.is_none() => {
+                day = Some(
+                    x.parse::<u8>()
+                        .map_err(|_| ArgsParseError::InvalidDay(arg))?,
+                )
+            }
+            x if part.is_none() => {
+                part = Some(
+                    x.parse::<Part>()
+                        .map_err(|_| ArgsParseError::InvalidPart(arg))?,
+                )
+            }
+            _ => return Err(ArgsParseError::UnknownCommand(arg)),
+        }
+    }
+
+    let day = day.ok_or(ArgsParseError::MissingDay)?;
+    let part = part.ok_or(ArgsParseError::MissingPart)?;
+
+    if !available.contains(&day) {
+        return Err(ArgsParseError::UnavailableDay(day));
+    }
+
+    Ok(Command::Submit(SubmitOptions {
+        day,
+        part,
+        input,
+        settings,
+    }))
+}
+
+fn parse_render<I>(mut args: I, available: &[u8]) -> Result<Command, ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut input = None;
+    let mut example = false;
+    let mut image = None;
+    let mut settings = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => {
+                let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                input = Some(PathBuf::from(value));
+            }
+            "--example" | "-e" => example = true,
+            "--image" => {
+                let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                image = Some(PathBuf::from(value));
+            }
+            x if SETTINGS.contains(&x) => settings.push(parse_setting(arg, &mut args)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            x if x.starts_with('-') => return Err(ArgsParseError::UnknownOption(arg)),
+            x if day.is_none() => {
+                day = Some(
+                    x.parse::<u8>()
+                        .map_err(|_| ArgsParseError::InvalidDay(arg))?,
+                )
+            }
+            _ => return Err(ArgsParseError::UnknownCommand(arg)),
+        }
+    }
+
+    let day = day.ok_or(ArgsParseError::MissingDay)?;
+
+    if !available.contains(&day) {
+        return Err(ArgsParseError::UnavailableDay(day));
+    }
+
+    if input.is_some() && example {
+        return Err(ArgsParseError::ConflictingOptions(
+            String::from("--input"),
+            String::from("--example"),
+        ));
+    }
+
+    Ok(Command::Render(RenderOptions {
+        day,
+        input,
+        example,
+        image,
+        settings,
+    }))
+}
+
+// Any day of the calendar, solved or not
+fn parse_puzzle_day(arg: String) -> Result<u8, ArgsParseError> {
+    arg.parse::<u8>()
+        .ok()
+        .filter(|day| (1..=25).contains(day))
+        .ok_or(ArgsParseError::InvalidDay(arg))
+}
+
+fn parse_example<I>(mut args: I) -> Result<Command, ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut force = false;
+    let mut show = false;
+    let mut settings = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--force" => force = true,
+            "--show" => show = true,
+            x if SETTINGS.contains(&x) => settings.push(parse_setting(arg, &mut args)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            x if x.starts_with('-') => return Err(ArgsParseError::UnknownOption(arg)),
+            _ if day.is_none() => day = Some(parse_puzzle_day(arg)?),
+            _ => return Err(ArgsParseError::UnknownCommand(arg)),
+        }
+    }
+
+    Ok(Command::Example(ExampleOptions {
+        day: day.ok_or(ArgsParseError::MissingDay)?,
+        force,
+        show,
+        settings,
+    }))
+}
+
+fn parse_new<I>(mut args: I, available: &[u8]) -> Result<Command, ArgsParseError>
+where
+    I: Iterator<Item = String>,
+{
+    let mut day = None;
+    let mut fetch = false;
+    let mut settings = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fetch" => fetch = true,
+            x if SETTINGS.contains(&x) => settings.push(parse_setting(arg, &mut args)?),
+            "-h" | "--help" => return Ok(Command::Help),
+            x if x.starts_with('-') => return Err(ArgsParseError::UnknownOption(arg)),
+            _ if day.is_none() => day = Some(parse_puzzle_day(arg)?),
+            _ => return Err(ArgsParseError::UnknownCommand(arg)),
+        }
+    }
+
+    let day = day.ok_or(ArgsParseError::MissingDay)?;
+
+    if available.contains(&day) {
+        return Err(ArgsParseError::ExistingDay(day));
+    }
+
+    Ok(Command::New(NewOptions {
+        day,
+        fetch,
+        settings,
+    }))
+}
+
+impl Command {
+    pub fn from_args<I>(args: I, available: &[u8]) -> Result<Self, ArgsParseError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut args = args.into_iter().peekable();
+
+        let bench = args.peek().is_some_and(|arg| arg == "bench");
+        match args.peek().map(|arg| arg.as_str()) {
+            Some("help" | "-h" | "--help") => return Ok(Command::Help),
+            Some("run" | "bench") => {
+                args.next();
+            }
+            Some("submit") => {
+                args.next();
+                return parse_submit(args, available);
+            }
+            Some("new") => {
+                args.next();
+                return parse_new(args, available);
+            }
+            Some("example") => {
+                args.next();
+                return parse_example(args);
+            }
+            Some("render") => {
+                args.next();
+                return parse_render(args, available);
+            }
+            Some(arg) if arg.starts_with('-') || arg.starts_with(|c: char| c.is_ascii_digit()) => {}
+            Some(arg) => return Err(ArgsParseError::UnknownCommand(arg.to_string())),
+            None => {}
+        };
+
+        let mut all = false;
+        let mut days = Vec::new();
+        let mut part = None;
+        let mut input = None;
+        let mut example = false;
+        let mut wait = false;
+        let mut jobs = 1;
+        let mut format = Format::Text;
+        let mut limits = Limits::default();
+        let mut settings = Vec::new();
+        let (mut runs, mut warmup, mut threshold) = (10, 3, 10);
+        let (mut baseline, mut save) = (None, None);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--all" | "-a" => all = true,
+                "--part" | "-p" => {
+                    let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                    let value = value
+                        .parse::<Part>()
+                        .map_err(|_| ArgsParseError::InvalidPart(value))?;
+                    part = Some(value);
+                }
+                "--input" | "-i" => {
+                    let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                    input = Some(PathBuf::from(value));
+                }
+                "--example" | "-e" => example = true,
+                "--wait" | "-w" => wait = true,
+                "--jobs" | "-j" if !bench => jobs = parse_count(arg, &mut args, 1)?,
+                "--format" | "-f" if !bench => {
+                    let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                    format = value
+                        .parse()
+                        .map_err(|_| ArgsParseError::InvalidFormat(value))?;
+                }
+                "--timeout" | "-t" if !bench => limits.day = Some(parse_seconds(arg, &mut args)?),
+                "--part-timeout" if !bench => limits.part = Some(parse_seconds(arg, &mut args)?),
+                "--runs" | "-n" if bench => runs = parse_count(arg, &mut args, 1)?,
+                "--warmup" if bench => warmup = parse_count(arg, &mut args, 0)?,
+                "--threshold" if bench => threshold = parse_count(arg, &mut args, 0)?,
+                "--baseline" if bench => {
+                    let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                    baseline = Some(PathBuf::from(value));
+                }
+                "--save" if bench => {
+                    let value = args.next().ok_or(ArgsParseError::MissingValue(arg))?;
+                    save = Some(PathBuf::from(value));
+                }
+                x if SETTINGS.contains(&x) => settings.push(parse_setting(arg, &mut args)?),
+                "-h" | "--help" => return Ok(Command::Help),
+                x if x.starts_with('-') => return Err(ArgsParseError::UnknownOption(arg)),
+                x => days.append(&mut parse_days(x)?),
+            }
+        }
+
+        if all || days.is_empty() {
+            days = available.to_vec();
+        }
+
+        days.sort_unstable();
+        days.dedup();
+
+        if let Some(day) = days.iter().find(|day| !available.contains(day)) {
+            return Err(ArgsParseError::UnavailableDay(*day));
+        }
+
+        if input.is_some() && example {
+            return Err(ArgsParseError::ConflictingOptions(
+                String::from("--input"),
+                String::from("--example"),
+            ));
+        }
+
+        // A single input file can't feed several days
+        if input.is_some() && days.len() != 1 {
+            return Err(ArgsParseError::InputForManyDays);
+        }
+
+        let run = RunOptions {
+            days,
+            part,
+            input,
+            example,
+            wait,
+            jobs,
+            format,
+            limits,
+            settings,
+        };
+
+        match bench {
+            true => Ok(Command::Bench(BenchOptions {
+                run,
+                runs,
+                warmup,
+                threshold,
+                baseline,
+                save,
+            })),
+            false => Ok(Command::Run(run)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AVAILABLE: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+    fn parse(args: &[&str]) -> Result<Command, ArgsParseError> {
+        Command::from_args(args.iter().map(|arg| arg.to_string()), &AVAILABLE)
+    }
+
+    // What a run gets for the days when no other option is given
+    fn run_options(days: Vec<u8>) -> RunOptions {
+        RunOptions {
+            days,
+            part: None,
+            input: None,
+            example: false,
+            wait: false,
+            jobs: 1,
+            format: Format::Text,
+            limits: Limits::default(),
+            settings: vec![],
+        }
+    }
+
+    #[test]
+    fn test_command_from_args_empty() {
+        let result = parse(&[]);
+        assert_eq!(result, Ok(Command::Run(run_options(AVAILABLE.to_vec()))));
+    }
+
+    #[test]
+    fn test_command_from_args_single_day() {
+        let result = parse(&["run", "7"]);
+        assert_eq!(result, Ok(Command::Run(run_options(vec![7]))));
+    }
+
+    #[test]
+    fn test_command_from_args_single_day_part() {
+        let result = parse(&["run", "7", "--part", "2"]);
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunOptions {
+                part: Some(Part::Two),
+                ..run_options(vec![7])
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_range() {
+        let result = parse(&["run", "3..5"]);
+        assert_eq!(result, Ok(Command::Run(run_options(vec![3, 4, 5]))));
+    }
+
+    #[test]
+    fn test_command_from_args_range_inclusive() {
+        let result = parse(&["3..=4", "11"]);
+        assert_eq!(result, Ok(Command::Run(run_options(vec![3, 4, 11]))));
+    }
+
+    #[test]
+    fn test_command_from_args_all() {
+        let result = parse(&["run", "--all", "-p", "1"]);
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunOptions {
+                part: Some(Part::One),
+                ..run_options(AVAILABLE.to_vec())
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_unavailable_day_err() {
+        let result = parse(&["run", "25"]);
+        assert_eq!(result, Err(ArgsParseError::UnavailableDay(25)));
+    }
+
+    #[test]
+    fn test_command_from_args_invalid_range_err() {
+        let result = parse(&["run", "9..3"]);
+        assert_eq!(
+            result,
+            Err(ArgsParseError::InvalidDay(String::from("9..3")))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_invalid_part_err() {
+        let result = parse(&["run", "1", "--part", "3"]);
+        assert_eq!(result, Err(ArgsParseError::InvalidPart(String::from("3"))));
+    }
+
+    #[test]
+    fn test_command_from_args_missing_part_err() {
+        let result = parse(&["run", "1", "--part"]);
+        assert_eq!(
+            result,
+            Err(ArgsParseError::MissingValue(String::from("--part")))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_unknown_command_err() {
+        let result = parse(&["fly"]);
+        assert_eq!(
+            result,
+            Err(ArgsParseError::UnknownCommand(String::from("fly")))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_input() {
+        let result = parse(&["run", "5", "--input", "-"]);
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunOptions {
+                input: Some(PathBuf::from("-")),
+                ..run_options(vec![5])
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_input_many_days_err() {
+        let result = parse(&["run", "3..5", "-i", "input.txt"]);
+        assert_eq!(result, Err(ArgsParseError::InputForManyDays));
+    }
+
+    #[test]
+    fn test_command_from_args_example() {
+        let result = parse(&["12", "--example", "-w"]);
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunOptions {
+                example: true,
+                wait: true,
+                ..run_options(vec![12])
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_example_with_input_err() {
+        let result = parse(&["12", "-e", "-i", "-"]);
+        assert_eq!(
+            result,
+            Err(ArgsParseError::ConflictingOptions(
+                String::from("--input"),
+                String::from("--example")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_submit() {
+        let result = parse(&["submit", "7", "2", "-i", "-"]);
+        assert_eq!(
+            result,
+            Ok(Command::Submit(SubmitOptions {
+                day: 7,
+                part: Part::Two,
+                input: Some(PathBuf::from("-")),
+                settings: vec![]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_submit_missing_part_err() {
+        let result = parse(&["submit", "7"]);
+        assert_eq!(result, Err(ArgsParseError::MissingPart));
+    }
+
+    #[test]
+    fn test_command_from_args_submit_unavailable_day_err() {
+        let result = parse(&["submit", "25", "1"]);
+        assert_eq!(result, Err(ArgsParseError::UnavailableDay(25)));
+    }
+
+    #[test]
+    fn test_command_from_args_settings() {
+        let result = parse(&["submit", "1", "2", "--year", "2021", "--token-file", "t"]);
+        assert_eq!(
+            result,
+            Ok(Command::Submit(SubmitOptions {
+                day: 1,
+                part: Part::Two,
+                input: None,
+                settings: vec![
+                    (String::from("year"), String::from("2021")),
+                    (String::from("token_file"), String::from("t"))
+                ]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_missing_setting_err() {
+        let result = parse(&["run", "1", "--cache-dir"]);
+        assert_eq!(
+            result,
+            Err(ArgsParseError::MissingValue(String::from("--cache-dir")))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_jobs() {
+        let result = parse(&["1..3", "--jobs", "4"]);
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunOptions {
+                jobs: 4,
+                ..run_options(vec![1, 2, 3])
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_bench() {
+        let result = parse(&["bench", "11", "-p", "2", "-n", "5", "--baseline", "b"]);
+        assert_eq!(
+            result,
+            Ok(Command::Bench(BenchOptions {
+                run: RunOptions {
+                    part: Some(Part::Two),
+                    ..run_options(vec![11])
+                },
+                runs: 5,
+                warmup: 3,
+                threshold: 10,
+                baseline: Some(PathBuf::from("b")),
+                save: None
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_bench_options_err() {
+        assert_eq!(
+            parse(&["run", "--runs", "5"]),
+            Err(ArgsParseError::UnknownOption(String::from("--runs")))
+        );
+        assert_eq!(
+            parse(&["bench", "--jobs", "4"]),
+            Err(ArgsParseError::UnknownOption(String::from("--jobs")))
+        );
+        assert_eq!(
+            parse(&["bench", "--runs", "0"]),
+            Err(ArgsParseError::InvalidNumber(
+                String::from("--runs"),
+                String::from("0")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_invalid_jobs_err() {
+        let result = parse(&["-j", "0"]);
+        assert_eq!(
+            result,
+            Err(ArgsParseError::InvalidNumber(
+                String::from("-j"),
+                String::from("0")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_format() {
+        let result = parse(&["10", "--format", "ndjson"]);
+        assert!(matches!(
+            result,
+            Ok(Command::Run(RunOptions {
+                format: Format::Ndjson,
+                ..
+            }))
+        ));
+        assert_eq!(
+            parse(&["10", "-f", "yaml"]),
+            Err(ArgsParseError::InvalidFormat(String::from("yaml")))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_timeouts() {
+        let result = parse(&["12", "-t", "30", "--part-timeout", "2.5"]);
+        assert!(matches!(
+            result,
+            Ok(Command::Run(RunOptions {
+                limits: Limits {
+                    day: Some(day),
+                    part: Some(part),
+                },
+                ..
+            })) if day == Duration::from_secs(30) && part == Duration::from_millis(2500)
+        ));
+        assert_eq!(
+            parse(&["12", "--timeout", "soon"]),
+            Err(ArgsParseError::InvalidNumber(
+                String::from("--timeout"),
+                String::from("soon")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_new() {
+        let result = parse(&["new", "13", "--fetch", "--year", "2022"]);
+        assert_eq!(
+            result,
+            Ok(Command::New(NewOptions {
+                day: 13,
+                fetch: true,
+                settings: vec![(String::from("year"), String::from("2022"))]
+            }))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_new_err() {
+        assert_eq!(parse(&["new", "12"]), Err(ArgsParseError::ExistingDay(12)));
+        assert_eq!(
+            parse(&["new", "26"]),
+            Err(ArgsParseError::InvalidDay(String::from("26")))
+        );
+        assert_eq!(parse(&["new"]), Err(ArgsParseError::MissingDay));
+    }
+
+    #[test]
+    fn test_command_from_args_example_command() {
+        assert_eq!(
+            parse(&["example", "12", "--force", "--show"]),
+            Ok(Command::Example(ExampleOptions {
+                day: 12,
+                force: true,
+                show: true,
+                settings: vec![]
+            }))
+        );
+        assert_eq!(parse(&["example"]), Err(ArgsParseError::MissingDay));
+        assert_eq!(
+            parse(&["example", "0"]),
+            Err(ArgsParseError::InvalidDay(String::from("0")))
+        );
+    }
+
+    #[test]
+    fn test_command_from_args_render() {
+        assert_eq!(
+            parse(&["render", "8", "-e", "--image", "forest.ppm"]),
+            Ok(Command::Render(RenderOptions {
+                day: 8,
+                input: None,
+                example: true,
+                image: Some(PathBuf::from("forest.ppm")),
+                settings: vec![]
+            }))
+        );
+        assert_eq!(
+            parse(&["render", "8", "-e", "-i", "my.txt"]),
+            Err(ArgsParseError::ConflictingOptions(
+                String::from("--input"),
+                String::from("--example")
+            ))
+        );
+        assert_eq!(
+            parse(&["render", "13"]),
+            Err(ArgsParseError::UnavailableDay(13))
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::error::ParseError;
+
+// Where the session cookie comes from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TokenSource {
+    Env(String),
+    File(PathBuf),
+}
+
+// Settings are layered, each layer overriding the previous one:
+// defaults, config file, environment variables and command-line flags
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub base_url: String,
+    pub year: u16,
+    pub cache_dir: PathBuf,
+    pub token: TokenSource,
+    pub user_agent: String,
+    pub contact: Option<String>,
+    pub min_interval: Duration,
+    pub retries: u32,
+}
+
+// Setting name and the environment variable overriding it
+const ENV_VARS: [(&str, &str); 9] = [
+    ("base_url", "AOC_BASE_URL"),
+    ("year", "AOC_YEAR"),
+    ("cache_dir", "AOC_CACHE_DIR"),
+    ("token_env", "AOC_TOKEN_ENV"),
+    ("token_file", "AOC_TOKEN_FILE"),
+    ("user_agent", "AOC_USER_AGENT"),
+    ("contact", "AOC_CONTACT"),
+    ("min_interval", "AOC_MIN_INTERVAL"),
+    ("retries", "AOC_RETRIES"),
+];
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum SettingError {
+    InvalidNumber(String),
+    InvalidYear(String),
+    MissingValue,
+    UnknownSetting(String),
+}
+
+impl std::fmt::Display for SettingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidNumber(s) => write!(f, "Invalid number {:?}", s),
+            Self::InvalidYear(s) => write!(f, "Invalid year {:?}", s),
+            Self::MissingValue => write!(f, "Expected \"setting = value\""),
+            Self::UnknownSetting(s) => write!(f, "Unknown setting {:?}", s),
+        }
+    }
+}
+
+impl std::error::Error for SettingError {}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    File(PathBuf, io::Error),
+    Parse(PathBuf, ParseError),
+    Setting(String, SettingError),
+    Token(TokenSource, Option<io::Error>),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path, _) => write!(f, "Unable to read {}", path.display()),
+            Self::Parse(path, _) => write!(f, "Unable to parse {}", path.display()),
+            Self::Setting(origin, _) => write!(f, "Invalid setting from {}", origin),
+            Self::Token(TokenSource::Env(var), _) => write!(f, "No ${} defined", var),
+            Self::Token(TokenSource::File(path), _) => {
+                write!(f, "No token found in {}", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(_, error) => Some(error),
+            Self::Parse(_, error) => Some(error),
+            Self::Setting(_, error) => Some(error),
+            Self::Token(_, Some(error)) => Some(error),
+            Self::Token(_, None) => None,
+        }
+    }
+}
+
+// Follows the XDG base directory spec, falling back to the usual dotfiles in $HOME
+fn xdg_dir(var: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(fallback)))
+        .map(|dir| dir.join("advent-of-code"))
+}
+
+fn default_config_file() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("config"))
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            base_url: String::from("https://adventofcode.com"),
+            year: 2022,
+            cache_dir: xdg_dir("XDG_CACHE_HOME", ".cache").unwrap_or(PathBuf::from(".cache")),
+            token: TokenSource::Env(String::from("AOC_TOKEN")),
+            user_agent: format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            contact: None,
+            min_interval: Duration::from_secs(3),
+            retries: 3,
+        }
+    }
+}
+
+impl Config {
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), SettingError> {
+        match name {
+            "base_url" => self.base_url = value.trim_end_matches('/').to_string(),
+            "year" => {
+                self.year = value
+                    .parse()
+                    .map_err(|_| SettingError::InvalidYear(value.to_string()))?
+            }
+            "cache_dir" => self.cache_dir = PathBuf::from(value),
+            "token_env" => self.token = TokenSource::Env(value.to_string()),
+            "token_file" => self.token = TokenSource::File(PathBuf::from(value)),
+            "user_agent" => self.user_agent = value.to_string(),
+            "contact" => self.contact = Some(value.to_string()).filter(|value| !value.is_empty()),
+            "min_interval" => {
+                self.min_interval = value
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .ok_or(SettingError::InvalidNumber(value.to_string()))?
+            }
+            "retries" => {
+                self.retries = value
+                    .parse()
+                    .map_err(|_| SettingError::InvalidNumber(value.to_string()))?
+            }
+            _ => return Err(SettingError::UnknownSetting(name.to_string())),
+        };
+
+        Ok(())
+    }
+
+    // One "setting = value" per line, blank lines and # comments are ignored
+    pub fn apply_file(&mut self, content: &str) -> Result<(), ParseError> {
+        for (index, line) in content.lines().enumerate() {
+            let line_content = line.split('#').next().unwrap_or_default().trim();
+            if line_content.is_empty() {
+                continue;
+            }
+
+            line_content
+                .split_once('=')
+                .ok_or(SettingError::MissingValue)
+                .and_then(|(name, value)| self.set(name.trim(), value.trim()))
+                .map_err(|error| ParseError::new(error).at_line(index + 1, line))?;
+        }
+
+        Ok(())
+    }
+
+    // An explicit config file must exist, the default one is optional
+    pub fn load(settings: &[(String, String)]) -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+
+        let explicit = settings
+            .iter()
+            .rev()
+            .find(|(name, _)| name == "config")
+            .map(|(_, path)| PathBuf::from(path))
+            .or_else(|| env::var_os("AOC_CONFIG").map(PathBuf::from));
+        let file = explicit.clone().or_else(default_config_file);
+
+        if let Some(path) = file {
+            match fs::read_to_string(&path) {
+                Ok(content) => config
+                    .apply_file(&content)
+                    .map_err(|error| ConfigError::Parse(path, error))?,
+                Err(error) if error.kind() == io::ErrorKind::NotFound && explicit.is_none() => {}
+                Err(error) => return Err(ConfigError::File(path, error)),
+            }
+        }
+
+        for (name, var) in ENV_VARS {
+            if let Ok(value) = env::var(var) {
+                config
+                    .set(name, &value)
+                    .map_err(|error| ConfigError::Setting(format!("${}", var), error))?;
+            }
+        }
+
+        for (name, value) in settings.iter().filter(|(name, _)| name != "config") {
+            config.set(name, value).map_err(|error| {
+                ConfigError::Setting(format!("--{}", name.replace('_', "-")), error)
+            })?;
+        }
+
+        Ok(config)
+    }
+
+    // Inputs and answers differ from one year to the next
+    pub fn year_dir(&self) -> PathBuf {
+        self.cache_dir.join(self.year.to_string())
+    }
+
+    pub fn token(&self) -> Result<String, ConfigError> {
+        let token = match &self.token {
+            TokenSource::Env(var) => env::var(var).ok(),
+            TokenSource::File(path) => Some(
+                fs::read_to_string(path)
+                    .map_err(|error| ConfigError::Token(self.token.clone(), Some(error)))?,
+            ),
+        };
+
+        token
+            .map(|token| token.trim().to_string())
+            .filter(|token| !token.is_empty())
+            .ok_or(ConfigError::Token(self.token.clone(), None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_apply_file_ok() {
+        let mut config = Config::default();
+        config
+            .apply_file("# Mock server\nbase_url = http://127.0.0.1:8080/\n\nyear = 2021 # last year\ntoken_file = /tmp/token\n")
+            .unwrap();
+        assert_eq!(config.base_url, "http://127.0.0.1:8080");
+        assert_eq!(config.year, 2021);
+        assert_eq!(config.token, TokenSource::File(PathBuf::from("/tmp/token")));
+    }
+
+    #[test]
+    fn test_config_apply_file_err() {
+        let mut config = Config::default();
+        let result = config.apply_file("year = 2021\nyaer = 2020");
+        assert_eq!(
+            result,
+            Err(
+                ParseError::new(SettingError::UnknownSetting(String::from("yaer")))
+                    .at_line(2, "yaer = 2020")
+            )
+        );
+    }
+
+    #[test]
+    fn test_config_set_politeness() {
+        let mut config = Config::default();
+        config.set("min_interval", "1.5").unwrap();
+        config.set("contact", "elf@north.pole").unwrap();
+        assert_eq!(config.min_interval, Duration::from_millis(1500));
+        assert_eq!(config.contact, Some(String::from("elf@north.pole")));
+        assert_eq!(
+            config.set("min_interval", "-1"),
+            Err(SettingError::InvalidNumber(String::from("-1")))
+        );
+    }
+
+    #[test]
+    fn test_config_set_invalid_year_err() {
+        let result = Config::default().set("year", "MMXXII");
+        assert_eq!(
+            result,
+            Err(SettingError::InvalidYear(String::from("MMXXII")))
+        );
+    }
+
+    #[test]
+    fn test_config_load_flags() {
+        let settings = [
+            (String::from("year"), String::from("2020")),
+            (String::from("cache_dir"), String::from("/tmp/aoc")),
+        ];
+        let config = Config::load(&settings).unwrap();
+        assert_eq!(config.year, 2020);
+        assert_eq!(config.year_dir(), PathBuf::from("/tmp/aoc/2020"));
+    }
+
+    #[test]
+    fn test_config_load_missing_file_err() {
+        let settings = [(String::from("config"), String::from("/nonexistent/config"))];
+        let result = Config::load(&settings);
+        assert!(matches!(result, Err(ConfigError::File(_, _))));
+    }
+
+    #[test]
+    fn test_config_token_file() {
+        let path = env::temp_dir().join(format!("aoc-token-{}", std::process::id()));
+        fs::write(&path, "s3cr3t\n").unwrap();
+        let config = Config {
+            token: TokenSource::File(path.clone()),
+            ..Config::default()
+        };
+        let result = config.token();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap(), "s3cr3t");
+    }
+
+    #[test]
+    fn test_config_token_missing_err() {
+        let config = Config {
+            token: TokenSource::Env(String::from("AOC_TOKEN_THAT_IS_NOT_SET")),
+            ..Config::default()
+        };
+        assert!(matches!(
+            config.token(),
+            Err(ConfigError::Token(TokenSource::Env(_), None))
+        ));
+    }
+}
@@ -1,149 +1,93 @@
-type Calories = i32;
-
-#[derive(Debug, Eq, PartialEq)]
-pub struct InventoryParseError;
+use std::str::FromStr;
 
-impl std::fmt::Display for InventoryParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unable to parse inventory")
-    }
-}
+use crate::error::{parse_lines, ParseError, SolutionError};
+use crate::solution::Solution;
 
-impl std::error::Error for InventoryParseError {}
+type Calories = i32;
 
 #[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
 struct Inventory {
     value: Vec<Calories>,
 }
 
-impl std::iter::IntoIterator for Inventory {
-    type Item = Calories;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.value.into_iter()
-    }
-}
-
-#[derive(Debug, Eq, PartialEq)]
-pub struct TroupeParseError;
-
-impl std::fmt::Display for TroupeParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Unable to parse troupe")
-    }
-}
-
-impl std::error::Error for TroupeParseError {}
-
 // "Troupe of elves", courtesy of 93 seconds long research
 #[derive(Debug, Eq, PartialEq)]
-struct Troupe {
+pub struct Troupe {
     value: Vec<Inventory>,
 }
 
-impl std::str::FromStr for Inventory {
-    type Err = InventoryParseError;
+impl FromStr for Inventory {
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (results, errors): (Vec<_>, Vec<_>) = s
-            .lines()
-            .map(|line| line.parse::<i32>())
-            .partition(Result::is_ok);
-
-        if !errors.is_empty() {
-            return Err(InventoryParseError);
-        }
-
         Ok(Inventory {
-            value: results.iter().flatten().cloned().collect(),
+            value: parse_lines::<Calories>(s)?,
         })
     }
 }
 
-impl std::str::FromStr for Troupe {
-    type Err = TroupeParseError;
+impl FromStr for Troupe {
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (results, errors): (Vec<_>, Vec<_>) = s
-            .split("\n\n")
-            .map(|inventory| inventory.parse::<Inventory>())
-            .partition(Result::is_ok);
-
-        if !errors.is_empty() {
-            return Err(TroupeParseError);
+        let mut value = Vec::new();
+        let mut line = 0;
+
+        for input in s.split("\n\n") {
+            value.push(
+                input
+                    .parse::<Inventory>()
+                    .map_err(|error| error.after(line))?,
+            );
+
+            // Account for the blank line between inventories
+            line += input.lines().count() + 1;
         }
 
-        Ok(Troupe {
-            value: results.iter().flatten().cloned().collect(),
-        })
+        Ok(Troupe { value })
     }
 }
 
-impl std::iter::IntoIterator for Troupe {
-    type Item = Inventory;
-    type IntoIter = std::vec::IntoIter<Self::Item>;
-
-    fn into_iter(self) -> Self::IntoIter {
-        self.value.into_iter()
-    }
-}
+pub struct Puzzle;
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum SolutionError {
-    InventoryParseError(InventoryParseError),
-    TroupeParseError(TroupeParseError),
-}
+impl Solution for Puzzle {
+    type Input = Troupe;
+    type AnswerOne = Calories;
+    type AnswerTwo = Calories;
+    type Error = SolutionError;
 
-impl std::fmt::Display for SolutionError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SolutionError::InventoryParseError(err) => err.fmt(f),
-            SolutionError::TroupeParseError(err) => err.fmt(f),
-        }
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<Troupe>()?)
     }
-}
 
-impl std::error::Error for SolutionError {}
+    fn part_one(troupe: &Troupe) -> Result<Calories, SolutionError> {
+        let result: Calories = troupe
+            .value
+            .iter()
+            .map(|inventory| inventory.value.iter().sum())
+            .max()
+            .ok_or(SolutionError::NoAnswer)?;
 
-impl From<InventoryParseError> for SolutionError {
-    fn from(error: InventoryParseError) -> Self {
-        SolutionError::InventoryParseError(error)
+        Ok(result)
     }
-}
 
-impl From<TroupeParseError> for SolutionError {
-    fn from(error: TroupeParseError) -> Self {
-        SolutionError::TroupeParseError(error)
-    }
-}
+    fn part_two(troupe: &Troupe) -> Result<Calories, SolutionError> {
+        let mut result: Vec<Calories> = troupe
+            .value
+            .iter()
+            .map(|inventory| inventory.value.iter().sum())
+            .collect();
 
-pub fn solve_first(input: &String) -> Result<String, SolutionError> {
-    let troupe = input.parse::<Troupe>()?;
-    let result: Calories = troupe
-        .into_iter()
-        .map(|inventory| inventory.into_iter().sum())
-        .max()
-        .ok_or(InventoryParseError)?;
-
-    Ok(result.to_string())
-}
+        result.sort();
+        result.reverse();
+        result.truncate(3);
 
-pub fn solve_second(input: &String) -> Result<String, SolutionError> {
-    let troupe = input.parse::<Troupe>()?;
-    let mut result: Vec<Calories> = troupe
-        .into_iter()
-        .map(|inventory| inventory.into_iter().sum())
-        .collect();
-
-    result.sort();
-    result.reverse();
-    result.truncate(3);
-
-    Ok(result.iter().sum::<i32>().to_string())
+        Ok(result.iter().sum())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::read_fixture;
 
     #[test]
     fn test_inventory_from_str_ok() {
@@ -159,7 +103,10 @@ mod tests {
     #[test]
     fn test_inventory_from_str_err() {
         let result = "1000\nBOOM\n3000".parse::<Inventory>();
-        assert_eq!(result, Err(InventoryParseError));
+        assert_eq!(
+            result,
+            Err(ParseError::new("invalid digit found in string").at_line(2, "BOOM"))
+        );
     }
 
     #[test]
@@ -182,7 +129,41 @@ mod tests {
 
     #[test]
     fn test_troupe_from_str_err() {
-        let result = "1000\n2000\n3000\n\nBOOM\n5000".parse::<Troupe>();
-        assert_eq!(result, Err(TroupeParseError));
+        let result = "1000\n2000\n3000\n\n4000\nBOOM".parse::<Troupe>();
+        assert_eq!(
+            result,
+            Err(ParseError::new("invalid digit found in string").at_line(6, "BOOM"))
+        );
+    }
+
+    #[test]
+    fn test_solve_part_1_ok() {
+        let input = Puzzle::parse(&read_fixture(1)).unwrap();
+        let result = Puzzle::part_one(&input).unwrap();
+        assert_eq!(result, 24000);
+    }
+
+    #[test]
+    fn test_solve_part_2_ok() {
+        let input = Puzzle::parse(&read_fixture(1)).unwrap();
+        let result = Puzzle::part_two(&input).unwrap();
+        assert_eq!(result, 45000);
+    }
+
+    #[test]
+    fn test_solve_parse_err() {
+        let result = Puzzle::parse("BOOM");
+        assert_eq!(
+            result,
+            Err(SolutionError::Parse(
+                ParseError::new("invalid digit found in string").at_line(1, "BOOM")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_solve_part_1_empty_err() {
+        let troupe = Troupe { value: vec![] };
+        assert_eq!(Puzzle::part_one(&troupe), Err(SolutionError::NoAnswer));
     }
 }
@@ -1,19 +1,29 @@
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Debug)]
+use crate::error::{parse_lines, ParseError, SolutionError};
+use crate::ocr;
+use crate::solution::Solution;
+
+const CRT_WIDTH: usize = 40;
+const CRT_HEIGHT: usize = 6;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 struct InstructionParseError;
 
+impl std::fmt::Display for InstructionParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Expected \"noop\" or \"addx N\"")
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 enum Instruction {
     AddX(i32),
     NoOp,
 }
 
-#[derive(Debug)]
-struct ProgramParseError;
-
 #[derive(Clone, Debug)]
-struct Program {
+pub struct Program {
     instructions: Vec<Instruction>,
     cycle: (usize, usize, Option<Instruction>), // (index, skip, next_instruction)
     x: i32,
@@ -22,7 +32,7 @@ struct Program {
 impl FromStr for Instruction {
     type Err = InstructionParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts = s.split_once(" ");
+        let parts = s.split_once(' ');
 
         match parts {
             Some(("addx", x)) => {
@@ -41,7 +51,7 @@ impl FromStr for Instruction {
 
 impl Program {
     fn new(instructions: Vec<Instruction>) -> Program {
-        let mut instructions = instructions.clone();
+        let mut instructions = instructions;
 
         let cycle = match instructions.pop() {
             Some(Instruction::AddX(x)) => (0, 3, Some(Instruction::AddX(x))),
@@ -64,14 +74,12 @@ impl Program {
 }
 
 impl FromStr for Program {
-    type Err = ProgramParseError;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let instructions: Vec<Instruction> = s
-            .lines()
-            .rev()
-            .map(|line| line.parse::<Instruction>())
-            .flatten()
-            .collect();
+        let mut instructions = parse_lines::<Instruction>(s)?;
+
+        // Instructions are popped from the end
+        instructions.reverse();
 
         Ok(Program::new(instructions))
     }
@@ -81,7 +89,7 @@ impl Iterator for Program {
     type Item = i32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let (cycle, skip, next_instruction) = self.cycle.clone();
+        let (cycle, skip, next_instruction) = self.cycle;
 
         if skip > 0 {
             self.cycle = (cycle + 1, skip - 1, next_instruction);
@@ -107,47 +115,58 @@ impl Iterator for Program {
     }
 }
 
-fn solve_first(input: &String) -> String {
-    let program = input.parse::<Program>().unwrap();
-    program
-        .zip(0..)
-        .skip(20)
-        .step_by(40)
-        .map(|x| x.0 * x.1)
-        .sum::<i32>()
-        .to_string()
-}
+pub struct Puzzle;
 
-fn solve_second(input: &String) -> String {
-    let program = input.parse::<Program>().unwrap();
-    let pixels = program
-        .skip(1)
-        .zip((0..40).cycle())
-        .map(|x| {
-            let (sprite_position, index) = x;
-            let pixel = if sprite_position.abs_diff(index) < 2 {
-                "#".to_string()
-            } else {
-                ".".to_string()
-            };
-            if index >= 39 {
-                pixel + "\n"
-            } else {
-                pixel
-            }
-        })
-        .collect::<String>();
+impl Solution for Puzzle {
+    type Input = Program;
+    type AnswerOne = i32;
+    type AnswerTwo = String;
+    type Error = SolutionError;
 
-    pixels.to_string()
-}
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<Program>()?)
+    }
+
+    fn part_one(program: &Program) -> Result<i32, Self::Error> {
+        Ok(program
+            .clone()
+            .zip(0..)
+            .skip(20)
+            .step_by(40)
+            .map(|x| x.0 * x.1)
+            .sum::<i32>())
+    }
 
-pub fn solve(input: String) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+    // Letters when they can be read, the CRT image otherwise
+    fn part_two(program: &Program) -> Result<String, Self::Error> {
+        let pixels = program
+            .clone()
+            .skip(1)
+            .zip((0..CRT_WIDTH as i32).cycle())
+            .take(CRT_WIDTH * CRT_HEIGHT)
+            .map(|x| {
+                let (sprite_position, index) = x;
+                let pixel = if sprite_position.abs_diff(index) < 2 {
+                    "#".to_string()
+                } else {
+                    ".".to_string()
+                };
+                if index as usize == CRT_WIDTH - 1 {
+                    pixel + "\n"
+                } else {
+                    pixel
+                }
+            })
+            .collect::<String>();
+
+        Ok(ocr::recognize(&pixels).unwrap_or(pixels))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::read_fixture;
 
     #[test]
     fn test_instruction_parse_no_op() {
@@ -167,6 +186,21 @@ mod tests {
         assert_eq!(actual, Instruction::AddX(-5));
     }
 
+    #[test]
+    fn test_instruction_parse_err() {
+        let actual = "addx".parse::<Instruction>();
+        assert_eq!(actual, Err(InstructionParseError));
+    }
+
+    #[test]
+    fn test_program_parse_err() {
+        let actual = "noop\naddx 3\nsubx 5".parse::<Program>();
+        assert_eq!(
+            actual.err(),
+            Some(ParseError::new(InstructionParseError).at_line(3, "subx 5"))
+        );
+    }
+
     #[test]
     fn test_program_result_1() {
         let actual: Vec<i32> = "noop\naddx 3\naddx -5"
@@ -187,18 +221,17 @@ mod tests {
 
     #[test]
     fn test_solution_1() {
-        let input = String::from("addx 15\naddx -11\naddx 6\naddx -3\naddx 5\naddx -1\naddx -8\naddx 13\naddx 4\nnoop\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx -35\naddx 1\naddx 24\naddx -19\naddx 1\naddx 16\naddx -11\nnoop\nnoop\naddx 21\naddx -15\nnoop\nnoop\naddx -3\naddx 9\naddx 1\naddx -3\naddx 8\naddx 1\naddx 5\nnoop\nnoop\nnoop\nnoop\nnoop\naddx -36\nnoop\naddx 1\naddx 7\nnoop\nnoop\nnoop\naddx 2\naddx 6\nnoop\nnoop\nnoop\nnoop\nnoop\naddx 1\nnoop\nnoop\naddx 7\naddx 1\nnoop\naddx -13\naddx 13\naddx 7\nnoop\naddx 1\naddx -33\nnoop\nnoop\nnoop\naddx 2\nnoop\nnoop\nnoop\naddx 8\nnoop\naddx -1\naddx 2\naddx 1\nnoop\naddx 17\naddx -9\naddx 1\naddx 1\naddx -3\naddx 11\nnoop\nnoop\naddx 1\nnoop\naddx 1\nnoop\nnoop\naddx -13\naddx -19\naddx 1\naddx 3\naddx 26\naddx -30\naddx 12\naddx -1\naddx 3\naddx 1\nnoop\nnoop\nnoop\naddx -9\naddx 18\naddx 1\naddx 2\nnoop\nnoop\naddx 9\nnoop\nnoop\nnoop\naddx -1\naddx 2\naddx -37\naddx 1\naddx 3\nnoop\naddx 15\naddx -21\naddx 22\naddx -6\naddx 1\nnoop\naddx 2\naddx 1\nnoop\naddx -10\nnoop\nnoop\naddx 20\naddx 1\naddx 2\naddx 2\naddx -6\naddx -11\nnoop\nnoop\nnoop");
-        let result = solve_first(&input);
+        let program = Puzzle::parse(&read_fixture(10)).unwrap();
+        let result = Puzzle::part_one(&program).unwrap();
 
-        assert_eq!(result, "13140");
+        assert_eq!(result, 13140);
     }
 
     #[test]
     fn test_solution_2() {
-        let input = String::from("addx 15\naddx -11\naddx 6\naddx -3\naddx 5\naddx -1\naddx -8\naddx 13\naddx 4\nnoop\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx -35\naddx 1\naddx 24\naddx -19\naddx 1\naddx 16\naddx -11\nnoop\nnoop\naddx 21\naddx -15\nnoop\nnoop\naddx -3\naddx 9\naddx 1\naddx -3\naddx 8\naddx 1\naddx 5\nnoop\nnoop\nnoop\nnoop\nnoop\naddx -36\nnoop\naddx 1\naddx 7\nnoop\nnoop\nnoop\naddx 2\naddx 6\nnoop\nnoop\nnoop\nnoop\nnoop\naddx 1\nnoop\nnoop\naddx 7\naddx 1\nnoop\naddx -13\naddx 13\naddx 7\nnoop\naddx 1\naddx -33\nnoop\nnoop\nnoop\naddx 2\nnoop\nnoop\nnoop\naddx 8\nnoop\naddx -1\naddx 2\naddx 1\nnoop\naddx 17\naddx -9\naddx 1\naddx 1\naddx -3\naddx 11\nnoop\nnoop\naddx 1\nnoop\naddx 1\nnoop\nnoop\naddx -13\naddx -19\naddx 1\naddx 3\naddx 26\naddx -30\naddx 12\naddx -1\naddx 3\naddx 1\nnoop\nnoop\nnoop\naddx -9\naddx 18\naddx 1\naddx 2\nnoop\nnoop\naddx 9\nnoop\nnoop\nnoop\naddx -1\naddx 2\naddx -37\naddx 1\naddx 3\nnoop\naddx 15\naddx -21\naddx 22\naddx -6\naddx 1\nnoop\naddx 2\naddx 1\nnoop\naddx -10\nnoop\nnoop\naddx 20\naddx 1\naddx 2\naddx 2\naddx -6\naddx -11\nnoop\nnoop\nnoop");
-        let result = solve_second(&input);
+        let program = Puzzle::parse(&read_fixture(10)).unwrap();
+        let result = Puzzle::part_two(&program).unwrap();
 
-        // TODO: Fix unnecessary bonus pixel
-        assert_eq!(result, "##..##..##..##..##..##..##..##..##..##..\n###...###...###...###...###...###...###.\n####....####....####....####....####....\n#####.....#####.....#####.....#####.....\n######......######......######......####\n#######.......#######.......#######.....\n.");
+        assert_eq!(result, "##..##..##..##..##..##..##..##..##..##..\n###...###...###...###...###...###...###.\n####....####....####....####....####....\n#####.....#####.....#####.....#####.....\n######......######......######......####\n#######.......#######.......#######.....\n");
     }
 }
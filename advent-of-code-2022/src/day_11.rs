@@ -1,7 +1,12 @@
 use std::str::FromStr;
 
+use crate::cancel;
+use crate::error::{ParseError, SolutionError};
+use crate::solution::Solution;
+
 type ItemRecipient = usize;
 type ItemWorryLevel = u128;
+type PassedItem = (ItemRecipient, ItemWorryLevel);
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum ItemCooldown {
@@ -25,7 +30,7 @@ impl FromStr for MonkeyOperationArg {
             "old" => Ok(MonkeyOperationArg::Old),
             x => x
                 .parse::<ItemWorryLevel>()
-                .map(|x| MonkeyOperationArg::Literal(x))
+                .map(MonkeyOperationArg::Literal)
                 .map_err(|_| MonkeyOperationArgParseError),
         }
     }
@@ -44,7 +49,7 @@ impl FromStr for MonkeyOperation {
     type Err = MonkeyOperationParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let input = s.trim().replace("Operation: new = ", "");
-        let mut input = input.split(" ");
+        let mut input = input.split(' ');
 
         let left_arg = input
             .next()
@@ -111,10 +116,22 @@ impl FromStr for MonkeyTest {
 #[derive(Debug, Eq, PartialEq)]
 enum MonkeyParseError {
     InvalidIndex,
-    InvalidInput,
     InvalidItem,
     InvalidOperation,
     InvalidTest,
+    MissingLine,
+}
+
+impl std::fmt::Display for MonkeyParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidIndex => write!(f, "Invalid monkey index"),
+            Self::InvalidItem => write!(f, "Invalid starting item"),
+            Self::InvalidOperation => write!(f, "Invalid operation"),
+            Self::InvalidTest => write!(f, "Invalid test"),
+            Self::MissingLine => write!(f, "Incomplete monkey description"),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -127,7 +144,7 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn inspect(&mut self, cooldown: &ItemCooldown) -> Vec<(ItemRecipient, ItemWorryLevel)> {
+    fn inspect(&mut self, cooldown: &ItemCooldown) -> Vec<PassedItem> {
         let result = self
             .items
             .iter()
@@ -146,17 +163,13 @@ impl Monkey {
         use MonkeyOperation::*;
         use MonkeyOperationArg::*;
 
+        let arg = |arg| match arg {
+            Literal(literal) => literal,
+            Old => *value,
+        };
         let next_value = match self.operation {
-            Add(left, right) => match (left, right) {
-                (Old, Old) => value + value,
-                (Literal(literal), Old) | (Old, Literal(literal)) => literal + value,
-                _ => unimplemented!(),
-            },
-            Multiply(left, right) => match (left, right) {
-                (Old, Old) => value.pow(2),
-                (Literal(literal), Old) | (Old, Literal(literal)) => literal * value,
-                _ => unimplemented!(),
-            },
+            Add(left, right) => arg(left) + arg(right),
+            Multiply(left, right) => arg(left) * arg(right),
         };
 
         match cooldown {
@@ -166,7 +179,7 @@ impl Monkey {
     }
 
     fn apply_test(&self, item: &ItemWorryLevel) -> ItemRecipient {
-        if item % self.test.0.clone() == 0 {
+        if item.is_multiple_of(self.test.0) {
             self.test.1
         } else {
             self.test.2
@@ -179,15 +192,15 @@ impl FromStr for Monkey {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut lines = s.lines();
 
-        let index_line = lines.next().ok_or(MonkeyParseError::InvalidInput)?;
+        let index_line = lines.next().ok_or(MonkeyParseError::MissingLine)?;
         let index = index_line
             .chars()
-            .filter(|c| c.is_digit(10))
+            .filter(|c| c.is_ascii_digit())
             .collect::<String>()
             .parse::<ItemRecipient>()
             .map_err(|_| MonkeyParseError::InvalidIndex)?;
 
-        let items_line = lines.next().ok_or(MonkeyParseError::InvalidInput)?;
+        let items_line = lines.next().ok_or(MonkeyParseError::MissingLine)?;
         let items = items_line
             .trim()
             .replace("Starting items:", "")
@@ -217,11 +230,8 @@ impl FromStr for Monkey {
     }
 }
 
-#[derive(Debug)]
-struct GameParseError;
-
 #[derive(Clone, Debug)]
-struct Game {
+pub struct Game {
     cooldown: ItemCooldown,
     monkeys: Vec<Monkey>,
 }
@@ -233,7 +243,7 @@ impl Game {
         sizes.reverse();
         sizes.truncate(2);
 
-        sizes.iter().fold(1, |acc, x| acc * x)
+        sizes.iter().product()
     }
 
     fn use_modulo_cooldown(&mut self) {
@@ -243,38 +253,47 @@ impl Game {
 }
 
 impl FromStr for Game {
-    type Err = GameParseError;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (result, errors): (Vec<_>, Vec<_>) = s
-            .split("\n\n")
-            .map(|input| input.parse::<Monkey>())
-            .partition(Result::is_ok);
-
-        if errors.is_empty() {
-            Ok(Game {
-                cooldown: ItemCooldown::Division,
-                monkeys: result.iter().flatten().cloned().collect(),
-            })
-        } else {
-            Err(GameParseError)
+        let mut monkeys = Vec::new();
+        let mut line = 1;
+
+        for input in s.split("\n\n") {
+            let monkey = input.parse::<Monkey>().map_err(|error| {
+                let text = input.lines().next().unwrap_or_default();
+                ParseError::new(error).at_line(line, text)
+            })?;
+
+            monkeys.push(monkey);
+
+            // Account for the blank line between monkeys
+            line += input.lines().count() + 1;
         }
+
+        Ok(Game {
+            cooldown: ItemCooldown::Division,
+            monkeys,
+        })
     }
 }
 
 impl Iterator for Game {
     type Item = Game;
 
+    // Endless, unless the runner gave up on the day
     fn next(&mut self) -> Option<Self::Item> {
-        let mut passed_items: Vec<(ItemRecipient, ItemWorryLevel)> = Vec::new();
+        if cancel::cancelled() {
+            return None;
+        }
+
+        let mut passed_items: Vec<PassedItem> = Vec::new();
 
         for monkey in self.monkeys.iter_mut() {
-            let (received_items, next_passed_items): (
-                Vec<(ItemRecipient, ItemWorryLevel)>,
-                Vec<(ItemRecipient, ItemWorryLevel)>,
-            ) = passed_items
-                .iter()
-                .cloned()
-                .partition(|item| item.0 == monkey.index);
+            let (received_items, next_passed_items): (Vec<PassedItem>, Vec<PassedItem>) =
+                passed_items
+                    .iter()
+                    .cloned()
+                    .partition(|item| item.0 == monkey.index);
 
             passed_items = next_passed_items;
 
@@ -298,44 +317,43 @@ impl Iterator for Game {
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum SolutionError {
-    Unknown,
-}
+pub struct Puzzle;
 
-impl std::fmt::Display for SolutionError {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SolutionError::Unknown => panic!("Unknown error"),
-        }
-    }
-}
+impl Solution for Puzzle {
+    type Input = Game;
+    type AnswerOne = u128;
+    type AnswerTwo = u128;
+    type Error = SolutionError;
 
-impl std::error::Error for SolutionError {}
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<Game>()?)
+    }
 
-pub fn solve_first(input: &String) -> Result<String, SolutionError> {
-    let rounds = 20;
-    let mut game = input.parse::<Game>().unwrap();
-    let endgame = game.nth(rounds - 1).ok_or(SolutionError::Unknown)?;
-    Ok(endgame.as_monkey_business().to_string())
-}
+    fn part_one(game: &Game) -> Result<u128, SolutionError> {
+        let rounds = 20;
+        let mut game = game.clone();
+        let endgame = game.nth(rounds - 1).ok_or(SolutionError::NoAnswer)?;
+        Ok(endgame.as_monkey_business())
+    }
 
-pub fn solve_second(input: &String) -> Result<String, SolutionError> {
-    let rounds = 10000;
-    let mut game = input.parse::<Game>().unwrap();
-    game.use_modulo_cooldown();
-    let endgame = game.nth(rounds - 1).ok_or(SolutionError::Unknown)?;
-    Ok(endgame.as_monkey_business().to_string())
+    fn part_two(game: &Game) -> Result<u128, SolutionError> {
+        let rounds = 10000;
+        let mut game = game.clone();
+        game.use_modulo_cooldown();
+        let endgame = game.nth(rounds - 1).ok_or(SolutionError::NoAnswer)?;
+        Ok(endgame.as_monkey_business())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::solution::read_fixture;
 
     #[test]
     fn test_monkey_from_str_item_recipient_invalid_input_err() {
         let result = "".parse::<Monkey>();
-        assert_eq!(result, Err(MonkeyParseError::InvalidInput))
+        assert_eq!(result, Err(MonkeyParseError::MissingLine))
     }
 
     #[test]
@@ -347,7 +365,7 @@ mod tests {
     #[test]
     fn test_monkey_from_str_items_invalid_input_err() {
         let result = "Monkey 0:".parse::<Monkey>();
-        assert_eq!(result, Err(MonkeyParseError::InvalidInput))
+        assert_eq!(result, Err(MonkeyParseError::MissingLine))
     }
 
     #[test]
@@ -368,4 +386,42 @@ mod tests {
             "Monkey 0:\n  Starting items: 10, 15\n  Operation: new = old * 19".parse::<Monkey>();
         assert_eq!(result, Err(MonkeyParseError::InvalidTest))
     }
+
+    #[test]
+    fn test_monkey_apply_operation_literals() {
+        let monkey = "Monkey 0:
+  Starting items: 10
+  Operation: new = 3 * 4
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 2"
+            .parse::<Monkey>()
+            .unwrap();
+        assert_eq!(monkey.apply_operation(&10, &ItemCooldown::Division), 4);
+        assert_eq!(monkey.apply_operation(&10, &ItemCooldown::Modulo(5)), 2);
+    }
+
+    #[test]
+    fn test_game_from_str_err() {
+        let input = read_fixture(11).replace("Starting items: 74", "Starting items: X");
+        let result = input.parse::<Game>().unwrap_err();
+        assert_eq!(
+            result,
+            ParseError::new("Invalid starting item").at_line(22, "Monkey 3:")
+        );
+    }
+
+    #[test]
+    fn test_solve_part_1_ok() {
+        let game = Puzzle::parse(&read_fixture(11)).unwrap();
+        let result = Puzzle::part_one(&game).unwrap();
+        assert_eq!(result, 10605);
+    }
+
+    #[test]
+    fn test_solve_part_2_ok() {
+        let game = Puzzle::parse(&read_fixture(11)).unwrap();
+        let result = Puzzle::part_two(&game).unwrap();
+        assert_eq!(result, 2713310158);
+    }
 }
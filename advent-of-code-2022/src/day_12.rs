@@ -1,256 +1,423 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    hash::Hash,
-    str::FromStr,
-};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use crate::error::{ParseError, SolutionError};
+use crate::grid::{Grid, Point};
+use crate::render::Render;
+use crate::search::{self, Path};
+use crate::solution::Solution;
 
 type Elevation = i32;
 
-enum Marker {
-    Start,
-    End,
-}
+// What a step from an elevation to the next costs, counted as at least 1 so that
+// A* stays exact
+type StepCost = fn(Elevation, Elevation) -> u32;
 
-#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-struct Position {
-    elevation: Elevation,
-    x: usize,
-    y: usize,
+// Every step is worth another, the answers count steps
+fn step(_: Elevation, _: Elevation) -> u32 {
+    1
 }
 
-impl Position {
-    fn new(x: usize, y: usize, elevation: Elevation) -> Self {
-        Position { x, y, elevation }
+// Climbing costs more than walking down or along
+fn climbing(from: Elevation, to: Elevation) -> u32 {
+    if to > from {
+        3
+    } else {
+        1
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Square {
-    moves: HashMap<Position, Elevation>,
-    position: Position,
+// Whatever the search, answers count steps and not costs
+#[derive(Clone, Copy)]
+enum Search {
+    AStar(StepCost),
+    BreadthFirst,
+    Dijkstra(StepCost),
 }
 
+// The routes drawn by the render command, each found its own way
+const ROUTES: [(&str, Search); 3] = [
+    ("Fewest steps, breadth-first", Search::BreadthFirst),
+    (
+        "Least climbing, climbs costing 3",
+        Search::Dijkstra(climbing),
+    ),
+    ("Fewest steps, A* towards E", Search::AStar(step)),
+];
+
 #[derive(Debug)]
-struct ElevationMap {
-    _from: usize,
-    _moves: HashMap<usize, Vec<usize>>,
-    _positions: Vec<Position>,
-    _to: usize,
+pub struct ElevationMap {
+    elevations: Grid<Elevation>,
+    end: Point,
+    start: Point,
 }
 
 impl ElevationMap {
-    fn new() -> Self {
-        ElevationMap {
-            _from: 0,
-            _moves: HashMap::new(),
-            _positions: Vec::new(),
-            _to: 0,
+    // Climbing more than one step of elevation at once needs gear we don't have
+    fn moves(&self, from: Point) -> impl Iterator<Item = Point> + '_ {
+        let elevation = self.elevations[from];
+        self.elevations
+            .neighbours(from)
+            .filter(move |to| self.elevations[*to] - elevation <= 1)
+    }
+
+    // Squares from which a single move reaches the point, the climb rule walked backwards
+    fn moves_back(&self, to: Point) -> impl Iterator<Item = Point> + '_ {
+        let elevation = self.elevations[to];
+        self.elevations
+            .neighbours(to)
+            .filter(move |from| elevation - self.elevations[*from] <= 1)
+    }
+
+    // Steps from every square that can reach the end, in one search descending from it
+    fn distances_to_end(&self) -> HashMap<Point, u32> {
+        search::distances([self.end], |point| self.moves_back(point))
+    }
+
+    // Shortest path to the end from any square matching the predicate, the first in
+    // reading order when several are as close
+    fn find_path_from<P>(&self, is_start: P) -> Option<Path<Point>>
+    where
+        P: Fn(Point) -> bool,
+    {
+        let distances = self.distances_to_end();
+        let (start, cost) = self
+            .elevations
+            .points()
+            .filter(|point| is_start(*point))
+            .filter_map(|point| Some((point, *distances.get(&point)?)))
+            .min_by_key(|(_, distance)| *distance)?;
+
+        // There always is a move to a square one step closer, until the end
+        let mut nodes = vec![start];
+        for distance in (0..cost).rev() {
+            let current = nodes[nodes.len() - 1];
+            let next = self
+                .moves(current)
+                .find(|next| distances.get(next) == Some(&distance))?;
+            nodes.push(next);
         }
+
+        Some(Path { nodes, cost })
+    }
+
+    fn edges(&self, from: Point, cost: StepCost) -> impl Iterator<Item = (Point, u32)> + '_ {
+        let elevation = self.elevations[from];
+        self.moves(from)
+            .map(move |to| (to, cost(elevation, self.elevations[to]).max(1)))
     }
 
-    fn add_move(&mut self, from_index: usize, to_index: usize) {
-        let from = self.get_position(from_index);
-        let to = self.get_position(to_index);
-        let is_valid = match (from, to) {
-            (Some(from), Some(to)) => {
-                let elevation_diff = to.elevation - from.elevation;
-                elevation_diff <= 1
-            }
-            _ => panic!("That’s weird"),
-        };
-
-        if is_valid {
-            self._moves
-                .entry(from_index)
-                .and_modify(|positions| positions.push(to_index))
-                .or_insert(vec![to_index]);
+    // The path as the arrows of the puzzle, every other square a dot
+    fn render_path(&self, path: &Path<Point>) -> String {
+        let mut squares = self.elevations.map(|_| '.');
+        for pair in path.nodes.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            squares[from] = match (to.row.cmp(&from.row), to.column.cmp(&from.column)) {
+                (Ordering::Less, _) => '^',
+                (Ordering::Greater, _) => 'v',
+                (_, Ordering::Less) => '<',
+                _ => '>',
+            };
+        }
+        if let Some(last) = path.nodes.last() {
+            squares[*last] = 'E';
         }
+        squares.to_string()
     }
 
-    fn add_position(&mut self, position: Position, marker: Option<Marker>) {
-        let next_index = self._positions.len();
+    fn find_path(&self, from: Point, to: Point, search: Search) -> Option<Path<Point>> {
+        match search {
+            Search::AStar(cost) => search::a_star(
+                from,
+                to,
+                |point| self.edges(point, cost),
+                // Every step costs at least 1 and gets at most 1 closer
+                |point| point.manhattan(to) as u32,
+            ),
+            Search::BreadthFirst => search::breadth_first(from, to, |point| self.moves(point)),
+            Search::Dijkstra(cost) => search::dijkstra(from, to, |point| self.edges(point, cost)),
+        }
+    }
+}
 
-        match marker {
-            Some(Marker::Start) => {
-                self._from = next_index;
-            }
-            Some(Marker::End) => {
-                self._to = next_index;
-            }
-            None => {}
-        };
+impl FromStr for ElevationMap {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut start, mut end) = (None, None);
+        let elevations = Grid::parse(s, |point, ch| {
+            let ch = match ch {
+                'S' => {
+                    start = Some(point);
+                    'a'
+                }
+                'E' => {
+                    end = Some(point);
+                    'z'
+                }
+                'a'..='z' => ch,
+                _ => return Err("Invalid elevation"),
+            };
+            Ok(ch as Elevation)
+        })?;
+
+        Ok(ElevationMap {
+            elevations,
+            end: end.ok_or(ParseError::new("Missing the best signal E"))?,
+            start: start.ok_or(ParseError::new("Missing the current position S"))?,
+        })
+    }
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    type Input = ElevationMap;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+    type Error = SolutionError;
 
-        self._positions.push(position);
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<ElevationMap>()?)
     }
 
-    fn get_moves(&self, key: &usize) -> Option<&Vec<usize>> {
-        self._moves.get(key)
+    fn part_one(elevation_map: &ElevationMap) -> Result<usize, SolutionError> {
+        let result = elevation_map
+            .find_path(elevation_map.start, elevation_map.end, Search::AStar(step))
+            .ok_or(SolutionError::NoAnswer)?;
+        Ok(result.steps())
     }
 
-    fn get_position(&self, index: usize) -> Option<&Position> {
-        self._positions.get(index)
+    fn part_two(elevation_map: &ElevationMap) -> Result<usize, SolutionError> {
+        let lowest = |point| elevation_map.elevations[point] == 'a' as Elevation;
+        let result = elevation_map
+            .find_path_from(lowest)
+            .ok_or(SolutionError::NoAnswer)?;
+
+        Ok(result.steps())
     }
 
-    fn get_start(&self) -> usize {
-        self._from
+    fn render(elevation_map: &ElevationMap) -> Option<Render> {
+        let (start, end) = (elevation_map.start, elevation_map.end);
+        let text = ROUTES
+            .iter()
+            .map(
+                |(name, search)| match elevation_map.find_path(start, end, *search) {
+                    Some(path) => format!(
+                        "{}: {} steps costing {}\n{}",
+                        name,
+                        path.steps(),
+                        path.cost,
+                        elevation_map.render_path(&path)
+                    ),
+                    None => format!("{}: no path from S to E\n", name),
+                },
+            )
+            .collect();
+
+        Some(Render { text, image: None })
     }
+}
 
-    fn get_end(&self) -> usize {
-        self._to
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::GridParseError;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_elevation_map_from_str_empty_err() {
+        let result = "".parse::<ElevationMap>().unwrap_err();
+        assert_eq!(result, ParseError::new(GridParseError::Empty));
     }
 
-    fn find_shortest_path(&self, from: &usize, to: &usize) -> Option<Vec<&Position>> {
-        let mut distance = HashMap::from([(from, 0)]);
-        let mut predecessors: HashMap<&usize, &usize> = HashMap::new();
-        let mut visited = HashSet::from([from]);
-        let mut queue = VecDeque::from([from]);
-
-        while let Some(node) = queue.pop_front() {
-            if let Some(adjacents) = self.get_moves(node) {
-                for adjacent in adjacents {
-                    if !visited.contains(adjacent) {
-                        visited.insert(adjacent);
-                        distance
-                            .entry(adjacent)
-                            .and_modify(|v| *v += 1)
-                            .or_insert(1);
-                        predecessors.insert(adjacent, node);
-                        queue.push_back(adjacent);
-
-                        if adjacent == to {
-                            break;
-                        }
-                    }
-                }
-            }
-        }
+    #[test]
+    fn test_elevation_map_from_str_uneven_err() {
+        let result = "Sab\nabcE".parse::<ElevationMap>().unwrap_err();
+        assert_eq!(
+            result,
+            ParseError::new(GridParseError::UnevenRow).at_line(2, "abcE")
+        );
+    }
 
-        let mut path = Vec::new();
-        let mut current: &usize = to;
+    #[test]
+    fn test_elevation_map_from_str_invalid_elevation_err() {
+        let result = "Sab\na1E".parse::<ElevationMap>().unwrap_err();
+        assert_eq!(
+            result,
+            ParseError::new("Invalid elevation")
+                .at_line(2, "a1E")
+                .at_column(2)
+        );
+    }
 
-        while let Some(predecessor) = predecessors.get(current).cloned() {
-            path.push(predecessor.clone());
-            current = predecessor;
-        }
+    #[test]
+    fn test_elevation_map_from_str_missing_end_err() {
+        let result = "Sab\nabc".parse::<ElevationMap>().unwrap_err();
+        assert_eq!(result, ParseError::new("Missing the best signal E"));
+    }
 
-        path.reverse();
+    #[test]
+    fn test_solve_part_1_ok() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let result = Puzzle::part_one(&elevation_map).unwrap();
+        assert_eq!(result, 31);
+    }
 
-        if path.len() == 0 {
-            None
-        } else {
-            Some(
-                path.iter()
-                    .map(|index| self.get_position(index.clone()))
-                    .flatten()
-                    .collect(),
-            )
-        }
+    #[test]
+    fn test_solve_part_2_ok() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let result = Puzzle::part_two(&elevation_map).unwrap();
+        assert_eq!(result, 29);
     }
 
-    fn positions(&self) -> std::slice::Iter<Position> {
-        self._positions.iter()
+    fn assert_walkable(elevation_map: &ElevationMap, path: &Path<Point>) {
+        assert_eq!(path.nodes.last(), Some(&elevation_map.end));
+        for pair in path.nodes.windows(2) {
+            assert!(elevation_map.moves(pair[0]).any(|to| to == pair[1]));
+        }
     }
-}
 
-impl FromStr for ElevationMap {
-    type Err = SolutionError;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut elevation_map = ElevationMap::new();
-
-        // Collect all positions first, we can derive moves from them
-        for (y, line) in s.lines().enumerate() {
-            for (x, ch) in line.chars().enumerate() {
-                let (marker, ch) = match ch {
-                    'S' => (Some(Marker::Start), 'a'),
-                    'E' => (Some(Marker::End), 'z'),
-                    ch => (None, ch),
-                };
-                let position = Position::new(x, y, ch as i32);
-                elevation_map.add_position(position, marker);
-            }
+    #[test]
+    fn test_find_path_searches_agree() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let (start, end) = (elevation_map.start, elevation_map.end);
+        for search in [
+            Search::AStar(step),
+            Search::BreadthFirst,
+            Search::Dijkstra(step),
+        ] {
+            let path = elevation_map.find_path(start, end, search).unwrap();
+            assert_eq!(path.nodes.first(), Some(&start));
+            assert_walkable(&elevation_map, &path);
+            assert_eq!((path.steps(), path.cost), (31, 31));
         }
+    }
 
-        let rows = s.lines().count();
-        let columns = elevation_map.positions().count() / rows;
-
-        let moves: Vec<(usize, usize)> =
-            elevation_map
-                .positions()
-                .enumerate()
-                .fold(Vec::new(), |acc, arg| {
-                    let mut next_acc = Vec::new();
-                    let (index, position) = arg;
-
-                    // UP
-                    if position.y > 0 {
-                        next_acc.push((index, position.x + (position.y - 1) * columns));
-                    }
-
-                    // DOWN
-                    if position.y < rows - 1 {
-                        next_acc.push((index, position.x + (position.y + 1) * columns));
-                    }
-
-                    // LEFT
-                    if position.x > 0 {
-                        next_acc.push((index, (position.x - 1) + position.y * columns));
-                    }
-
-                    // RIGHT
-                    if position.x < columns - 1 {
-                        next_acc.push((index, (position.x + 1) + position.y * columns));
-                    }
-
-                    [acc, next_acc].concat()
-                });
-
-        for (from_index, to_index) in moves {
-            elevation_map.add_move(from_index, to_index);
+    #[test]
+    fn test_find_path_climbing_costs() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let (start, end) = (elevation_map.start, elevation_map.end);
+        let dijkstra = elevation_map
+            .find_path(start, end, Search::Dijkstra(climbing))
+            .unwrap();
+        let a_star = elevation_map
+            .find_path(start, end, Search::AStar(climbing))
+            .unwrap();
+        assert_walkable(&elevation_map, &dijkstra);
+        assert_walkable(&elevation_map, &a_star);
+        assert_eq!(dijkstra.cost, a_star.cost);
+        // 25 climbs from a to z at least, every other step costing 1
+        assert!(dijkstra.cost >= 31 + 2 * 25);
+    }
+
+    // Free descents, that would let A* overestimate were they not counted as 1
+    fn sliding(from: Elevation, to: Elevation) -> u32 {
+        if to < from {
+            0
+        } else {
+            2
         }
+    }
 
-        Ok(elevation_map)
+    #[test]
+    fn test_find_path_free_steps() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let (start, end) = (elevation_map.start, elevation_map.end);
+        let dijkstra = elevation_map
+            .find_path(start, end, Search::Dijkstra(sliding))
+            .unwrap();
+        let a_star = elevation_map
+            .find_path(start, end, Search::AStar(sliding))
+            .unwrap();
+        assert_walkable(&elevation_map, &a_star);
+        assert_eq!(dijkstra.cost, a_star.cost);
+        assert!(a_star.cost >= a_star.steps() as u32);
     }
-}
 
-#[derive(Debug, Eq, PartialEq)]
-pub enum SolutionError {
-    Unknown,
-}
+    #[test]
+    fn test_render_routes() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let text = Puzzle::render(&elevation_map).unwrap().text;
+        assert!(text.starts_with(
+            "Fewest steps, breadth-first: 31 steps costing 31\n\
+             >>vv<<<<\n\
+             ..vvv<<^\n\
+             ..vv>E^^\n\
+             ..v>>>^^\n\
+             ..>>>>>^\n"
+        ));
+        assert_eq!(text.matches("31 steps").count(), ROUTES.len());
+    }
 
-impl std::fmt::Display for SolutionError {
-    fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Unknown => panic!("Unknown error"),
-        }
+    #[test]
+    fn test_render_path_unreachable() {
+        let elevation_map = "Sbz\nbcE".parse::<ElevationMap>().unwrap();
+        let text = Puzzle::render(&elevation_map).unwrap().text;
+        assert!(text.starts_with("Fewest steps, breadth-first: no path from S to E\n"));
+    }
+
+    #[test]
+    fn test_find_path_unreachable() {
+        let elevation_map = "Sbz\nbcE".parse::<ElevationMap>().unwrap();
+        let (start, end) = (elevation_map.start, elevation_map.end);
+        assert_eq!(
+            elevation_map.find_path(start, end, Search::BreadthFirst),
+            None
+        );
+        assert_eq!(
+            elevation_map.find_path(start, end, Search::AStar(step)),
+            None
+        );
     }
-}
 
-impl std::error::Error for SolutionError {}
+    #[test]
+    fn test_find_path_from() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let start = elevation_map.start;
+        let from_start = elevation_map
+            .find_path_from(|point| point == start)
+            .unwrap();
+        assert_eq!(
+            Some(from_start.clone()),
+            elevation_map.find_path(start, elevation_map.end, Search::BreadthFirst)
+        );
+
+        let path = elevation_map
+            .find_path_from(|point| elevation_map.elevations[point] == 'a' as Elevation)
+            .unwrap();
+        assert_walkable(&elevation_map, &path);
+        assert_eq!((path.steps(), path.cost), (29, 29));
+        assert_eq!(path.nodes[0], Point { row: 4, column: 0 });
+    }
 
-pub fn solve_first(input: &String) -> Result<String, SolutionError> {
-    let elevation_map = input.parse::<ElevationMap>()?;
-    let start = elevation_map.get_start();
-    let end = elevation_map.get_end();
-    let result = elevation_map
-        .find_shortest_path(&start, &end)
-        .ok_or(SolutionError::Unknown)?;
-    Ok(result.len().to_string())
-}
+    #[test]
+    fn test_distances_to_end_match_forward_searches() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let distances = elevation_map.distances_to_end();
+        for point in elevation_map.elevations.points() {
+            let forward = elevation_map
+                .find_path(point, elevation_map.end, Search::BreadthFirst)
+                .map(|path| path.cost);
+            assert_eq!(distances.get(&point).copied(), forward, "{:?}", point);
+        }
+    }
 
-pub fn solve_second(input: &String) -> Result<String, SolutionError> {
-    let elevation_map = input.parse::<ElevationMap>()?;
-    let result = elevation_map
-        .positions()
-        .enumerate()
-        .filter(|index_position| index_position.1.elevation == 'a' as i32)
-        .map(|start| {
-            let end = elevation_map.get_end();
-            elevation_map.find_shortest_path(&start.0, &end)
-        })
-        .flatten()
-        .map(|route| route.len())
-        .min()
-        .ok_or(SolutionError::Unknown)?;
+    #[test]
+    fn test_distances_from_every_lowest_square() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        let lowest = elevation_map
+            .elevations
+            .iter()
+            .filter(|(_, elevation)| **elevation == 'a' as Elevation)
+            .map(|(point, _)| point);
+        let distances = search::distances(lowest, |point| elevation_map.moves(point));
+        assert_eq!(distances.get(&elevation_map.end), Some(&29));
+    }
 
-    Ok(result.to_string())
+    #[test]
+    fn test_find_path_from_nowhere() {
+        let elevation_map = Puzzle::parse(&read_fixture(12)).unwrap();
+        assert_eq!(elevation_map.find_path_from(|_| false), None);
+    }
 }
@@ -1,3 +1,8 @@
+use std::str::FromStr;
+
+use crate::error::{parse_lines, SolutionError};
+use crate::solution::Solution;
+
 const ROCK: i32 = 1;
 const PAPER: i32 = 2;
 const SCISSORS: i32 = 3;
@@ -6,110 +11,189 @@ const LOSS: i32 = 0;
 const DRAW: i32 = 3;
 const WIN: i32 = 6;
 
+#[derive(Debug, Eq, PartialEq)]
 enum ChoiceScore {
-    ROCK,
-    PAPER,
-    SCISSORS,
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum RoundParseError {
+    InvalidOpponentChoice(String),
+    InvalidPlayerChoice(String),
+    IncompleteRound,
+}
+
+impl std::fmt::Display for RoundParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidOpponentChoice(s) => write!(f, "Invalid opponent choice {:?}", s),
+            Self::InvalidPlayerChoice(s) => write!(f, "Invalid player choice {:?}", s),
+            Self::IncompleteRound => write!(f, "Expected two choices"),
+        }
+    }
 }
 
-struct Round(ChoiceScore, ChoiceScore);
+#[derive(Debug, Eq, PartialEq)]
+pub struct Round(ChoiceScore, ChoiceScore);
 
-fn parse_input(lines: Vec<String>) -> Vec<Round> {
-    let mut result = Vec::new();
+impl FromStr for Round {
+    type Err = RoundParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (opponent_choice, player_choice) =
+            s.split_once(' ').ok_or(RoundParseError::IncompleteRound)?;
 
-    for line in lines {
-        let choices: Vec<&str> = line.split(" ").collect();
-        let opponent_choice = match choices.first() {
-            Some(&"A") => ChoiceScore::ROCK,
-            Some(&"B") => ChoiceScore::PAPER,
-            Some(&"C") => ChoiceScore::SCISSORS,
-            Some(&&_) => panic! {"wat"},
-            None => panic! {"oops"},
+        let opponent_choice = match opponent_choice {
+            "A" => ChoiceScore::Rock,
+            "B" => ChoiceScore::Paper,
+            "C" => ChoiceScore::Scissors,
+            x => return Err(RoundParseError::InvalidOpponentChoice(x.to_string())),
         };
-        let player_choice = match choices.last() {
-            Some(&"X") => ChoiceScore::ROCK,
-            Some(&"Y") => ChoiceScore::PAPER,
-            Some(&"Z") => ChoiceScore::SCISSORS,
-            Some(&&_) => panic! {"wat"},
-            None => panic! {"oops"},
+        let player_choice = match player_choice {
+            "X" => ChoiceScore::Rock,
+            "Y" => ChoiceScore::Paper,
+            "Z" => ChoiceScore::Scissors,
+            x => return Err(RoundParseError::InvalidPlayerChoice(x.to_string())),
         };
-        result.push(Round(opponent_choice, player_choice))
-    }
 
-    return result;
+        Ok(Round(opponent_choice, player_choice))
+    }
 }
 
-fn solve_first(rounds: &Vec<Round>) -> String {
+fn solve_first(rounds: &[Round]) -> i32 {
     let mut score: i32 = 0;
     for round in rounds {
         let round_score: i32 = match round {
-            Round(ChoiceScore::ROCK, choice) => match choice {
-                ChoiceScore::ROCK => ROCK + DRAW,
-                ChoiceScore::PAPER => PAPER + WIN,
-                ChoiceScore::SCISSORS => SCISSORS + LOSS,
+            Round(ChoiceScore::Rock, choice) => match choice {
+                ChoiceScore::Rock => ROCK + DRAW,
+                ChoiceScore::Paper => PAPER + WIN,
+                ChoiceScore::Scissors => SCISSORS + LOSS,
             },
-            Round(ChoiceScore::PAPER, choice) => match choice {
-                ChoiceScore::ROCK => ROCK + LOSS,
-                ChoiceScore::PAPER => PAPER + DRAW,
-                ChoiceScore::SCISSORS => SCISSORS + WIN,
+            Round(ChoiceScore::Paper, choice) => match choice {
+                ChoiceScore::Rock => ROCK + LOSS,
+                ChoiceScore::Paper => PAPER + DRAW,
+                ChoiceScore::Scissors => SCISSORS + WIN,
             },
-            Round(ChoiceScore::SCISSORS, choice) => match choice {
-                ChoiceScore::ROCK => ROCK + WIN,
-                ChoiceScore::PAPER => PAPER + LOSS,
-                ChoiceScore::SCISSORS => SCISSORS + DRAW,
+            Round(ChoiceScore::Scissors, choice) => match choice {
+                ChoiceScore::Rock => ROCK + WIN,
+                ChoiceScore::Paper => PAPER + LOSS,
+                ChoiceScore::Scissors => SCISSORS + DRAW,
             },
         };
         score += round_score
     }
-    return score.to_string();
+    score
 }
 
-fn solve_second(rounds: &Vec<Round>) -> String {
+fn solve_second(rounds: &[Round]) -> i32 {
     let mut score: i32 = 0;
     for round in rounds {
         let desired_move = match &round {
             // Lose
-            Round(opponent_move, ChoiceScore::ROCK) => match opponent_move {
-                ChoiceScore::ROCK => ChoiceScore::SCISSORS,
-                ChoiceScore::PAPER => ChoiceScore::ROCK,
-                ChoiceScore::SCISSORS => ChoiceScore::PAPER,
+            Round(opponent_move, ChoiceScore::Rock) => match opponent_move {
+                ChoiceScore::Rock => ChoiceScore::Scissors,
+                ChoiceScore::Paper => ChoiceScore::Rock,
+                ChoiceScore::Scissors => ChoiceScore::Paper,
             },
             // Draw
-            Round(opponent_move, ChoiceScore::PAPER) => match opponent_move {
-                ChoiceScore::ROCK => ChoiceScore::ROCK,
-                ChoiceScore::PAPER => ChoiceScore::PAPER,
-                ChoiceScore::SCISSORS => ChoiceScore::SCISSORS,
+            Round(opponent_move, ChoiceScore::Paper) => match opponent_move {
+                ChoiceScore::Rock => ChoiceScore::Rock,
+                ChoiceScore::Paper => ChoiceScore::Paper,
+                ChoiceScore::Scissors => ChoiceScore::Scissors,
             },
             // Win
-            Round(opponent_move, ChoiceScore::SCISSORS) => match opponent_move {
-                ChoiceScore::ROCK => ChoiceScore::PAPER,
-                ChoiceScore::PAPER => ChoiceScore::SCISSORS,
-                ChoiceScore::SCISSORS => ChoiceScore::ROCK,
+            Round(opponent_move, ChoiceScore::Scissors) => match opponent_move {
+                ChoiceScore::Rock => ChoiceScore::Paper,
+                ChoiceScore::Paper => ChoiceScore::Scissors,
+                ChoiceScore::Scissors => ChoiceScore::Rock,
             },
         };
         let round_score: i32 = match round {
-            Round(ChoiceScore::ROCK, _) => match desired_move {
-                ChoiceScore::ROCK => ROCK + DRAW,
-                ChoiceScore::PAPER => PAPER + WIN,
-                ChoiceScore::SCISSORS => SCISSORS + LOSS,
+            Round(ChoiceScore::Rock, _) => match desired_move {
+                ChoiceScore::Rock => ROCK + DRAW,
+                ChoiceScore::Paper => PAPER + WIN,
+                ChoiceScore::Scissors => SCISSORS + LOSS,
             },
-            Round(ChoiceScore::PAPER, _) => match desired_move {
-                ChoiceScore::ROCK => ROCK + LOSS,
-                ChoiceScore::PAPER => PAPER + DRAW,
-                ChoiceScore::SCISSORS => SCISSORS + WIN,
+            Round(ChoiceScore::Paper, _) => match desired_move {
+                ChoiceScore::Rock => ROCK + LOSS,
+                ChoiceScore::Paper => PAPER + DRAW,
+                ChoiceScore::Scissors => SCISSORS + WIN,
             },
-            Round(ChoiceScore::SCISSORS, _) => match desired_move {
-                ChoiceScore::ROCK => ROCK + WIN,
-                ChoiceScore::PAPER => PAPER + LOSS,
-                ChoiceScore::SCISSORS => SCISSORS + DRAW,
+            Round(ChoiceScore::Scissors, _) => match desired_move {
+                ChoiceScore::Rock => ROCK + WIN,
+                ChoiceScore::Paper => PAPER + LOSS,
+                ChoiceScore::Scissors => SCISSORS + DRAW,
             },
         };
         score += round_score
     }
-    return score.to_string();
+    score
+}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    type Input = Vec<Round>;
+    type AnswerOne = i32;
+    type AnswerTwo = i32;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(parse_lines::<Round>(input)?)
+    }
+
+    fn part_one(rounds: &Vec<Round>) -> Result<i32, Self::Error> {
+        Ok(solve_first(rounds))
+    }
+
+    fn part_two(rounds: &Vec<Round>) -> Result<i32, Self::Error> {
+        Ok(solve_second(rounds))
+    }
 }
 
-pub fn solve(input: Vec<String>) -> (String, String) {
-    let data = parse_input(input);
-    return (solve_first(&data), solve_second(&data));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_round_from_str_ok() {
+        let result = "A Y".parse::<Round>();
+        assert_eq!(result, Ok(Round(ChoiceScore::Rock, ChoiceScore::Paper)));
+    }
+
+    #[test]
+    fn test_round_from_str_missing_choice_err() {
+        let result = "A".parse::<Round>();
+        assert_eq!(result, Err(RoundParseError::IncompleteRound));
+    }
+
+    #[test]
+    fn test_round_from_str_invalid_choice_err() {
+        let result = "D X".parse::<Round>();
+        assert_eq!(
+            result,
+            Err(RoundParseError::InvalidOpponentChoice(String::from("D")))
+        );
+    }
+
+    #[test]
+    fn test_solve_parse_err() {
+        let result = Puzzle::parse("A Y\nB W\nC Z");
+        assert_eq!(
+            result,
+            Err(SolutionError::Parse(
+                ParseError::new("Invalid player choice \"W\"").at_line(2, "B W")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_solve_ok() {
+        let rounds = Puzzle::parse(&read_fixture(2)).unwrap();
+        assert_eq!(Puzzle::part_one(&rounds), Ok(15));
+        assert_eq!(Puzzle::part_two(&rounds), Ok(12));
+    }
 }
@@ -1,29 +1,39 @@
+use std::str::FromStr;
+
+use crate::error::{parse_lines, ParseError, SolutionError};
+use crate::solution::Solution;
+
 struct Compartment {
     value: String,
 }
 
-struct Rucksack(Compartment, Compartment);
+pub struct Rucksack(Compartment, Compartment);
 
 struct Rucksacks(Vec<Rucksack>);
 
 struct Group(Compartment, Compartment, Compartment);
 struct Groups(Vec<Group>);
 
+pub struct Supplies {
+    rucksacks: Rucksacks,
+    groups: Groups,
+}
+
 const ASCII_LOWERCASE_A: u32 = 97;
 const ASCII_LOWERCASE_Z: u32 = 122;
 const ASCII_UPPERCASE_A: u32 = 65;
 const ASCII_UPPERCASE_Z: u32 = 90;
 
 trait Priority {
-    fn as_priority(&self) -> u32;
+    fn as_priority(&self) -> Option<u32>;
     fn to_priority(item: char) -> u32 {
         let code = item as u32;
 
-        return match code {
+        match code {
             (ASCII_LOWERCASE_A..=ASCII_LOWERCASE_Z) => code - ASCII_LOWERCASE_A + 1,
             (ASCII_UPPERCASE_A..=ASCII_UPPERCASE_Z) => code - ASCII_UPPERCASE_A + 27,
             _ => 0,
-        };
+        }
     }
 }
 
@@ -41,32 +51,56 @@ impl From<&str> for Compartment {
     }
 }
 
-impl From<&String> for Rucksack {
-    fn from(s: &String) -> Self {
+#[derive(Debug, Eq, PartialEq)]
+pub enum RucksackParseError {
+    InvalidItem(char),
+    NoSharedItem,
+    OddItemCount,
+}
+
+impl std::fmt::Display for RucksackParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidItem(c) => write!(f, "Invalid item {:?}", c),
+            Self::NoSharedItem => write!(f, "No item shared between compartments"),
+            Self::OddItemCount => write!(f, "Odd number of items"),
+        }
+    }
+}
+
+impl FromStr for Rucksack {
+    type Err = RucksackParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(c) = s.chars().find(|c| !c.is_ascii_alphabetic()) {
+            return Err(RucksackParseError::InvalidItem(c));
+        }
+
         let length = s.len();
+        if !length.is_multiple_of(2) {
+            return Err(RucksackParseError::OddItemCount);
+        }
+
         let (a, b) = s.split_at(length / 2);
+        let rucksack = Rucksack(Compartment::from(a), Compartment::from(b));
 
-        Rucksack(Compartment::from(a), Compartment::from(b))
+        match rucksack.as_priority() {
+            Some(_) => Ok(rucksack),
+            None => Err(RucksackParseError::NoSharedItem),
+        }
     }
 }
 
 impl Priority for Rucksack {
-    fn as_priority(&self) -> u32 {
+    fn as_priority(&self) -> Option<u32> {
         for a in self.0.value.chars() {
             for b in self.1.value.chars() {
                 if a == b {
-                    return Self::to_priority(b);
+                    return Some(Self::to_priority(b));
                 }
             }
         }
 
-        panic!("huh?")
-    }
-}
-
-impl From<&Vec<String>> for Rucksacks {
-    fn from(v: &Vec<String>) -> Self {
-        v.iter().map(|x| Rucksack::from(x)).collect()
+        None
     }
 }
 
@@ -81,21 +115,42 @@ impl FromIterator<Rucksack> for Rucksacks {
     }
 }
 
-impl From<&[String]> for Group {
-    fn from(s: &[String]) -> Self {
-        let mut compartments = s.iter().map(|x| Compartment::from(x));
+#[derive(Debug, Eq, PartialEq)]
+enum GroupParseError {
+    IncompleteGroup,
+    NoBadge,
+}
 
-        Group(
-            compartments.next().expect("no first compartment"),
-            compartments.next().expect("no second compartment"),
-            compartments.next().expect("no third compartment"),
-        )
+impl std::fmt::Display for GroupParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompleteGroup => write!(f, "Group has less than three rucksacks"),
+            Self::NoBadge => write!(f, "No badge shared by the group"),
+        }
     }
 }
 
-impl From<&Vec<String>> for Groups {
-    fn from(v: &Vec<String>) -> Self {
-        v.chunks(3).map(|x| Group::from(x)).collect()
+impl TryFrom<&[String]> for Group {
+    type Error = GroupParseError;
+    fn try_from(s: &[String]) -> Result<Self, Self::Error> {
+        let mut compartments = s.iter().map(Compartment::from);
+
+        let group = Group(
+            compartments
+                .next()
+                .ok_or(GroupParseError::IncompleteGroup)?,
+            compartments
+                .next()
+                .ok_or(GroupParseError::IncompleteGroup)?,
+            compartments
+                .next()
+                .ok_or(GroupParseError::IncompleteGroup)?,
+        );
+
+        match group.as_priority() {
+            Some(_) => Ok(group),
+            None => Err(GroupParseError::NoBadge),
+        }
     }
 }
 
@@ -111,7 +166,7 @@ impl FromIterator<Group> for Groups {
 }
 
 impl Priority for Group {
-    fn as_priority(&self) -> u32 {
+    fn as_priority(&self) -> Option<u32> {
         let mut ab = Vec::new();
         for a in self.0.value.chars() {
             for b in self.1.value.chars() {
@@ -123,30 +178,109 @@ impl Priority for Group {
 
         for c in self.2.value.chars() {
             if ab.contains(&c) {
-                return Self::to_priority(c);
+                return Some(Self::to_priority(c));
             }
         }
 
-        panic!("huh?")
+        None
     }
 }
 
-fn solve_first(input: &Vec<String>) -> String {
-    Rucksacks::from(input)
-        .0
-        .iter()
-        .fold(0, |sum, x| sum + x.as_priority())
-        .to_string()
+impl FromStr for Supplies {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rucksacks = parse_lines::<Rucksack>(s)?.into_iter().collect();
+
+        let lines: Vec<String> = s.lines().map(|line| line.to_string()).collect();
+        let groups = lines
+            .chunks(3)
+            .enumerate()
+            .map(|(index, chunk)| {
+                Group::try_from(chunk)
+                    .map_err(|error| ParseError::new(error).at_line(index * 3 + 1, &chunk[0]))
+            })
+            .collect::<Result<Groups, ParseError>>()?;
+
+        Ok(Supplies { rucksacks, groups })
+    }
 }
 
-fn solve_second(input: &Vec<String>) -> String {
-    Groups::from(input)
-        .0
-        .iter()
-        .fold(0, |sum, x| sum + x.as_priority())
-        .to_string()
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    type Input = Supplies;
+    type AnswerOne = u32;
+    type AnswerTwo = u32;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<Supplies>()?)
+    }
+
+    fn part_one(supplies: &Supplies) -> Result<u32, Self::Error> {
+        supplies
+            .rucksacks
+            .0
+            .iter()
+            .map(|x| x.as_priority())
+            .sum::<Option<u32>>()
+            .ok_or(SolutionError::NoAnswer)
+    }
+
+    fn part_two(supplies: &Supplies) -> Result<u32, Self::Error> {
+        supplies
+            .groups
+            .0
+            .iter()
+            .map(|x| x.as_priority())
+            .sum::<Option<u32>>()
+            .ok_or(SolutionError::NoAnswer)
+    }
 }
 
-pub fn solve(input: Vec<String>) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_rucksack_from_str_ok() {
+        let result = "vJrwpWtwJgWrhcsFMMfFFhFp".parse::<Rucksack>().unwrap();
+        assert_eq!(result.as_priority(), Some(16));
+    }
+
+    #[test]
+    fn test_rucksack_from_str_invalid_item_err() {
+        let result = "ab1b".parse::<Rucksack>();
+        assert_eq!(result.err(), Some(RucksackParseError::InvalidItem('1')));
+    }
+
+    #[test]
+    fn test_rucksack_from_str_odd_item_count_err() {
+        let result = "abcba".parse::<Rucksack>();
+        assert_eq!(result.err(), Some(RucksackParseError::OddItemCount));
+    }
+
+    #[test]
+    fn test_rucksack_from_str_no_shared_item_err() {
+        let result = "abcd".parse::<Rucksack>();
+        assert_eq!(result.err(), Some(RucksackParseError::NoSharedItem));
+    }
+
+    #[test]
+    fn test_supplies_from_str_incomplete_group_err() {
+        let input = read_fixture(3) + "aa";
+        let result = input.parse::<Supplies>();
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(GroupParseError::IncompleteGroup).at_line(7, "aa"))
+        );
+    }
+
+    #[test]
+    fn test_solve_ok() {
+        let supplies = Puzzle::parse(&read_fixture(3)).unwrap();
+        assert_eq!(Puzzle::part_one(&supplies), Ok(157));
+        assert_eq!(Puzzle::part_two(&supplies), Ok(70));
+    }
 }
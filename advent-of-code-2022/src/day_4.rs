@@ -1,44 +1,63 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, str::FromStr};
 
+use crate::error::{parse_lines, SolutionError};
+use crate::solution::Solution;
+
+#[derive(Debug, Eq, PartialEq)]
 struct Assignment {
     left: i32,
     right: i32,
 }
-struct Pair(Assignment, Assignment);
+#[derive(Debug, Eq, PartialEq)]
+pub struct Pair(Assignment, Assignment);
 
 trait Overlap {
     fn is_concealing(&self) -> bool;
     fn is_intersecting(&self) -> bool;
 }
 
-impl From<String> for Assignment {
-    fn from(s: String) -> Assignment {
-        let mut bounds = s.split("-").take(2);
-        let left = bounds
-            .next()
-            .expect("no left bound")
+#[derive(Debug, Eq, PartialEq)]
+pub enum AssignmentParseError {
+    InvalidBound(String),
+    MissingAssignment,
+    MissingBound,
+}
+
+impl std::fmt::Display for AssignmentParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidBound(s) => write!(f, "Invalid section bound {:?}", s),
+            Self::MissingAssignment => write!(f, "Expected two assignments"),
+            Self::MissingBound => write!(f, "Expected two section bounds"),
+        }
+    }
+}
+
+impl FromStr for Assignment {
+    type Err = AssignmentParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (left, right) = s
+            .split_once('-')
+            .ok_or(AssignmentParseError::MissingBound)?;
+        let left = left
             .parse::<i32>()
-            .expect("no valid left bound");
-        let right = bounds
-            .next()
-            .expect("no right bound")
+            .map_err(|_| AssignmentParseError::InvalidBound(left.to_string()))?;
+        let right = right
             .parse::<i32>()
-            .expect("no valid right bound");
+            .map_err(|_| AssignmentParseError::InvalidBound(right.to_string()))?;
 
-        Assignment { left, right }
+        Ok(Assignment { left, right })
     }
 }
 
-impl From<&String> for Pair {
-    fn from(s: &String) -> Pair {
-        let mut assignments = s
-            .split(",")
-            .take(2)
-            .map(|a| Assignment::from(a.to_string()));
-        let assignment_1 = assignments.next().expect("no left assignment found");
-        let assignment_2 = assignments.next().expect("no right assignment found");
+impl FromStr for Pair {
+    type Err = AssignmentParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (assignment_1, assignment_2) = s
+            .split_once(',')
+            .ok_or(AssignmentParseError::MissingAssignment)?;
 
-        Pair(assignment_1, assignment_2)
+        Ok(Pair(assignment_1.parse()?, assignment_2.parse()?))
     }
 }
 
@@ -57,26 +76,75 @@ impl Overlap for Pair {
     }
 }
 
-fn parse_input(data: Vec<String>) -> Vec<Pair> {
-    data.iter().map(|datum| Pair::from(datum)).collect()
-}
+pub struct Puzzle;
 
-fn solve_first(input: &Vec<Pair>) -> String {
-    input
-        .iter()
-        .fold(0, |c, p| if p.is_concealing() { c + 1 } else { c })
-        .to_string()
-}
+impl Solution for Puzzle {
+    type Input = Vec<Pair>;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(parse_lines::<Pair>(input)?)
+    }
+
+    fn part_one(input: &Vec<Pair>) -> Result<usize, Self::Error> {
+        Ok(input.iter().filter(|p| p.is_concealing()).count())
+    }
 
-fn solve_second(input: &Vec<Pair>) -> String {
-    input
-        .iter()
-        .fold(0, |c, p| if p.is_intersecting() { c + 1 } else { c })
-        .to_string()
+    fn part_two(input: &Vec<Pair>) -> Result<usize, Self::Error> {
+        Ok(input.iter().filter(|p| p.is_intersecting()).count())
+    }
 }
 
-pub fn solve(input: Vec<String>) -> (String, String) {
-    let data = parse_input(input);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_pair_from_str_ok() {
+        let result = "2-4,6-8".parse::<Pair>();
+        assert_eq!(
+            result,
+            Ok(Pair(
+                Assignment { left: 2, right: 4 },
+                Assignment { left: 6, right: 8 }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_pair_from_str_missing_assignment_err() {
+        let result = "2-4".parse::<Pair>();
+        assert_eq!(result, Err(AssignmentParseError::MissingAssignment));
+    }
 
-    (solve_first(&data), solve_second(&data))
+    #[test]
+    fn test_pair_from_str_invalid_bound_err() {
+        let result = "2-4,6-x".parse::<Pair>();
+        assert_eq!(
+            result,
+            Err(AssignmentParseError::InvalidBound(String::from("x")))
+        );
+    }
+
+    #[test]
+    fn test_solve_parse_err() {
+        let result = Puzzle::parse("2-4,6-8\n2-3");
+        assert_eq!(
+            result.err(),
+            Some(SolutionError::Parse(
+                ParseError::new(AssignmentParseError::MissingAssignment).at_line(2, "2-3")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_solve_ok() {
+        let pairs = Puzzle::parse(&read_fixture(4)).unwrap();
+        assert_eq!(Puzzle::part_one(&pairs), Ok(2));
+        assert_eq!(Puzzle::part_two(&pairs), Ok(4));
+    }
 }
@@ -1,67 +1,119 @@
+use std::str::FromStr;
+
+use crate::error::{ParseError, SolutionError};
+use crate::solution::Solution;
+
+#[derive(Clone)]
 struct Stack {
     value: Vec<char>,
 }
 
-struct Stacks {
+#[derive(Clone)]
+pub struct Stacks {
     value: Vec<Stack>,
 }
 
+#[derive(Debug, Eq, PartialEq)]
 struct Move {
     count: usize,
     from: usize,
     to: usize,
 }
 
-struct Moves {
+pub struct Moves {
     value: Vec<Move>,
 }
 
-impl From<&str> for Move {
-    fn from(s: &str) -> Self {
-        let cleaned = s
-            .replace("move ", "")
-            .replace(" from ", " ")
-            .replace(" to ", " ");
-        let mut data = cleaned.split(" ");
-        let count = data.next().unwrap().parse::<usize>().unwrap();
-        let from = data.next().unwrap().parse::<usize>().unwrap() - 1;
-        let to = data.next().unwrap().parse::<usize>().unwrap() - 1;
-        Move { count, from, to }
+#[derive(Debug, Eq, PartialEq)]
+enum MoveParseError {
+    InvalidFormat,
+    InvalidNumber(String),
+    UnknownStack(usize),
+}
+
+impl std::fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "Expected \"move N from A to B\""),
+            Self::InvalidNumber(s) => write!(f, "Invalid number {:?}", s),
+            Self::UnknownStack(stack) => write!(f, "Unknown stack {}", stack),
+        }
     }
 }
 
-impl From<&str> for Moves {
-    fn from(s: &str) -> Self {
-        Moves {
-            value: s.split('\n').map(|line| Move::from(line)).collect(),
+impl FromStr for Move {
+    type Err = MoveParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_number = |x: &str| {
+            x.parse::<usize>()
+                .map_err(|_| MoveParseError::InvalidNumber(x.to_string()))
+        };
+        // Stacks are numbered from 1 in the input
+        let parse_stack = |x: &str| {
+            parse_number(x)?
+                .checked_sub(1)
+                .ok_or(MoveParseError::UnknownStack(0))
+        };
+
+        match s.split(' ').collect::<Vec<&str>>()[..] {
+            ["move", count, "from", from, "to", to] => Ok(Move {
+                count: parse_number(count)?,
+                from: parse_stack(from)?,
+                to: parse_stack(to)?,
+            }),
+            _ => Err(MoveParseError::InvalidFormat),
         }
     }
 }
 
+impl Moves {
+    // Moves can only be validated against the stacks they are applied to
+    fn parse(s: &str, stacks: &Stacks, first_line: usize) -> Result<Self, ParseError> {
+        let stack_count = stacks.value.len();
+        let value = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                line.parse::<Move>()
+                    .and_then(|m| match (m.from, m.to) {
+                        (from, _) if from >= stack_count => {
+                            Err(MoveParseError::UnknownStack(from + 1))
+                        }
+                        (_, to) if to >= stack_count => Err(MoveParseError::UnknownStack(to + 1)),
+                        _ => Ok(m),
+                    })
+                    .map_err(|error| ParseError::new(error).at_line(first_line + index, line))
+            })
+            .collect::<Result<Vec<Move>, ParseError>>()?;
+
+        Ok(Moves { value })
+    }
+}
+
 impl Stack {
     fn push(&mut self, c: char) {
         self.value.push(c);
     }
-    fn pop(&mut self) -> char {
-        self.value.pop().expect("no crate to unload")
+    fn pop(&mut self) -> Option<char> {
+        self.value.pop()
     }
 }
 
 impl Stacks {
-    fn apply_move(&mut self, m: &Move, at_once: bool) -> &mut Self {
+    fn apply_move(&mut self, m: &Move, at_once: bool) -> Option<&mut Self> {
         let value: &mut Vec<Stack> = self.value.as_mut();
-        let from_stack = value.get_mut(m.from).expect("no stack to read from");
+        let from_stack = value.get_mut(m.from)?;
         let mut crates = Vec::new();
         for _ in 0..m.count {
-            crates.push(from_stack.pop());
+            crates.push(from_stack.pop()?);
         }
         if at_once {
             crates.reverse()
         }
-        let to_stack = value.get_mut(m.to).expect("no stack to write to");
+        let to_stack = value.get_mut(m.to)?;
         crates.iter().for_each(|c| to_stack.push(c.to_owned()));
 
-        self
+        Some(self)
     }
 }
 
@@ -69,7 +121,7 @@ impl From<&str> for Stacks {
     fn from(s: &str) -> Self {
         let mut value: Vec<Stack> = Vec::new();
         let (stack_count, stack_data) = s
-            .split("\n")
+            .lines()
             .map(|line| line.to_string())
             .map(|line| -> Vec<char> {
                 line.chars()
@@ -82,7 +134,7 @@ impl From<&str> for Stacks {
                     .collect()
             })
             .fold((0, Vec::new()), |acc, line| {
-                if line.len() > 0 {
+                if !line.is_empty() {
                     (line.len(), [line, acc.1].concat())
                 } else {
                     acc
@@ -95,7 +147,7 @@ impl From<&str> for Stacks {
                     .iter()
                     .skip(value.len())
                     .step_by(stack_count)
-                    .map(|c| c.clone())
+                    .copied()
                     .filter(|c| c.is_alphabetic())
                     .collect(),
             })
@@ -105,42 +157,117 @@ impl From<&str> for Stacks {
     }
 }
 
-fn parse_input(data: &String) -> (Stacks, Moves) {
-    let (raw_stacks, raw_moves) = data.split_once("\n\n").expect("no input separator");
+fn parse_input(data: &str) -> Result<(Stacks, Moves), ParseError> {
+    let (raw_stacks, raw_moves) = data
+        .split_once("\n\n")
+        .ok_or(ParseError::new("No blank line between stacks and moves"))?;
+
+    let stacks = Stacks::from(raw_stacks);
+    // Moves start after the stacks and the blank line
+    let moves = Moves::parse(raw_moves, &stacks, raw_stacks.lines().count() + 2)?;
 
-    (Stacks::from(raw_stacks), Moves::from(raw_moves))
+    Ok((stacks, moves))
 }
 
-fn solve_first(input: &String) -> String {
-    let (mut stacks, moves) = parse_input(input);
-    moves
-        .value
-        .iter()
-        .fold(&mut stacks, |s: &mut Stacks, m| {
-            let result = s.apply_move(m, false);
-            result
-        })
+// Crates run out when a move takes more than the stack holds
+fn rearrange(input: &(Stacks, Moves), at_once: bool) -> Option<String> {
+    let (stacks, moves) = input;
+    let mut stacks = stacks.clone();
+
+    for m in moves.value.iter() {
+        stacks.apply_move(m, at_once)?;
+    }
+
+    stacks
         .value
         .iter()
-        .map(|stack| stack.value.iter().last().unwrap())
+        .map(|stack| stack.value.last())
         .collect()
 }
 
-fn solve_second(input: &String) -> String {
-    let (mut stacks, moves) = parse_input(input);
-    moves
-        .value
-        .iter()
-        .fold(&mut stacks, |s: &mut Stacks, m| {
-            let result = s.apply_move(m, true);
-            result
-        })
-        .value
-        .iter()
-        .map(|stack| stack.value.iter().last().unwrap())
-        .collect()
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    type Input = (Stacks, Moves);
+    type AnswerOne = String;
+    type AnswerTwo = String;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(parse_input(input)?)
+    }
+
+    fn part_one(input: &(Stacks, Moves)) -> Result<String, Self::Error> {
+        rearrange(input, false).ok_or(SolutionError::NoAnswer)
+    }
+
+    fn part_two(input: &(Stacks, Moves)) -> Result<String, Self::Error> {
+        rearrange(input, true).ok_or(SolutionError::NoAnswer)
+    }
 }
 
-pub fn solve(input: String) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_move_from_str_ok() {
+        let result = "move 3 from 1 to 3".parse::<Move>();
+        assert_eq!(
+            result,
+            Ok(Move {
+                count: 3,
+                from: 0,
+                to: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_move_from_str_invalid_format_err() {
+        let result = "move 3 to 1".parse::<Move>();
+        assert_eq!(result, Err(MoveParseError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_move_from_str_invalid_number_err() {
+        let result = "move x from 1 to 3".parse::<Move>();
+        assert_eq!(
+            result,
+            Err(MoveParseError::InvalidNumber(String::from("x")))
+        );
+    }
+
+    #[test]
+    fn test_solve_parse_unknown_stack_err() {
+        let input = read_fixture(5).replace("move 2 from 2 to 1", "move 2 from 2 to 4");
+        let result = Puzzle::parse(&input);
+        assert_eq!(
+            result.err(),
+            Some(SolutionError::Parse(
+                ParseError::new(MoveParseError::UnknownStack(4)).at_line(8, "move 2 from 2 to 4")
+            ))
+        );
+    }
+
+    #[test]
+    fn test_solve_parse_missing_separator_err() {
+        let result = Puzzle::parse("[A]\n 1 \nmove 1 from 1 to 1");
+        assert!(matches!(result, Err(SolutionError::Parse(_))));
+    }
+
+    #[test]
+    fn test_solve_no_answer_err() {
+        let input = read_fixture(5).replace("move 3 from 1 to 3", "move 5 from 1 to 3");
+        let stacks = Puzzle::parse(&input).unwrap();
+        assert_eq!(Puzzle::part_one(&stacks), Err(SolutionError::NoAnswer));
+    }
+
+    #[test]
+    fn test_solve_ok() {
+        let stacks = Puzzle::parse(&read_fixture(5)).unwrap();
+        assert_eq!(Puzzle::part_one(&stacks), Ok(String::from("CMZ")));
+        assert_eq!(Puzzle::part_two(&stacks), Ok(String::from("MCD")));
+    }
 }
@@ -1,5 +1,8 @@
 use std::collections::{HashSet, VecDeque};
 
+use crate::error::SolutionError;
+use crate::solution::Solution;
+
 const START_OF_PACKET_MARKER_LENGTH: usize = 4;
 const START_OF_MESSAGE_MARKER_LENGTH: usize = 14;
 
@@ -11,7 +14,7 @@ fn find_signal(length: usize, s: &str) -> Option<usize> {
         if char_buffer.len() > length {
             char_buffer.pop_front();
             char_set.clear();
-            if char_buffer.iter().map(|x| char_set.insert(x.1)).all(|x| x) {
+            if char_buffer.iter().all(|x| char_set.insert(x.1)) {
                 return Some(x.0 + 1);
             }
         }
@@ -20,18 +23,42 @@ fn find_signal(length: usize, s: &str) -> Option<usize> {
     None
 }
 
-fn solve_first(input: &String) -> String {
-    find_signal(START_OF_PACKET_MARKER_LENGTH, input)
-        .unwrap()
-        .to_string()
-}
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    type Input = String;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.trim_end().to_string())
+    }
+
+    fn part_one(input: &String) -> Result<usize, Self::Error> {
+        find_signal(START_OF_PACKET_MARKER_LENGTH, input).ok_or(SolutionError::NoAnswer)
+    }
 
-fn solve_second(input: &String) -> String {
-    find_signal(START_OF_MESSAGE_MARKER_LENGTH, input)
-        .unwrap()
-        .to_string()
+    fn part_two(input: &String) -> Result<usize, Self::Error> {
+        find_signal(START_OF_MESSAGE_MARKER_LENGTH, input).ok_or(SolutionError::NoAnswer)
+    }
 }
 
-pub fn solve(input: String) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_solve_ok() {
+        let input = Puzzle::parse(&read_fixture(6)).unwrap();
+        assert_eq!(Puzzle::part_one(&input), Ok(7));
+        assert_eq!(Puzzle::part_two(&input), Ok(19));
+    }
+
+    #[test]
+    fn test_solve_no_answer_err() {
+        let input = Puzzle::parse("aaaaaaaa").unwrap();
+        assert_eq!(Puzzle::part_one(&input), Err(SolutionError::NoAnswer));
+    }
 }
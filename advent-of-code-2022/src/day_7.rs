@@ -1,10 +1,19 @@
 use std::{collections::HashMap, str::FromStr};
 
+use crate::error::{ParseError, SolutionError};
+use crate::solution::Solution;
+
 const TOTAL_SPACE: i32 = 70000000;
 const REQUIRED_SPACE: i32 = 30000000;
 
 #[derive(Debug)]
-struct TerminalLineParseError;
+pub struct TerminalLineParseError;
+
+impl std::fmt::Display for TerminalLineParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown terminal line")
+    }
+}
 
 #[derive(Debug, PartialEq, Eq)]
 enum TerminalLine {
@@ -14,9 +23,6 @@ enum TerminalLine {
     ListInput,
 }
 
-#[derive(Debug)]
-struct FileParseError;
-
 #[derive(Debug)]
 struct File {
     size: i32,
@@ -29,10 +35,16 @@ enum FileTreeEntry {
 }
 
 #[derive(Debug)]
-struct FileTreeParseError;
+pub struct FileTreeParseError;
+
+impl std::fmt::Display for FileTreeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Listing for an unknown directory")
+    }
+}
 
 #[derive(Debug)]
-struct FileTree {
+pub struct FileTree {
     value: FileTreeEntry,
 }
 
@@ -51,7 +63,7 @@ impl FromStr for TerminalLine {
         } else if let Some(directory_name) = s.strip_prefix("dir ") {
             Ok(TerminalLine::DirectoryOutput(directory_name.to_string()))
         } else {
-            let (size, name) = s.split_once(" ").ok_or(TerminalLineParseError)?;
+            let (size, name) = s.split_once(' ').ok_or(TerminalLineParseError)?;
 
             let name = name.to_string();
             let size = size.parse::<i32>().map_err(|_| TerminalLineParseError)?;
@@ -62,40 +74,43 @@ impl FromStr for TerminalLine {
 }
 
 impl FromStr for FileTree {
-    type Err = FileTreeParseError;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut tree = HashMap::new();
         let mut path = Vec::new();
-        for line in s.lines() {
-            if let Ok(line) = line.parse::<TerminalLine>() {
-                match line {
-                    TerminalLine::ChangeDirInput(x) => {
-                        if x == "/" {
-                            path.clear();
-                        } else if x == ".." {
-                            path.pop();
-                        } else {
-                            path.push(x);
-                        }
+        for (index, text) in s.lines().enumerate() {
+            let line = text
+                .parse::<TerminalLine>()
+                .map_err(|error| ParseError::new(error).at_line(index + 1, text))?;
+            match line {
+                TerminalLine::ChangeDirInput(x) => {
+                    if x == "/" {
+                        path.clear();
+                    } else if x == ".." {
+                        path.pop();
+                    } else {
+                        path.push(x);
                     }
-                    TerminalLine::DirectoryOutput(x) => {
-                        path.iter()
-                            .try_fold(&mut tree, |subtree, key| match subtree.get_mut(key) {
-                                Some(FileTreeEntry::DirEntry(x)) => Ok(x),
-                                _ => Err(FileTreeParseError),
-                            })?
-                            .insert(x, FileTreeEntry::DirEntry(HashMap::new()));
-                    }
-                    TerminalLine::FileOutput(name, size) => {
-                        path.iter()
-                            .try_fold(&mut tree, |subtree, key| match subtree.get_mut(key) {
-                                Some(FileTreeEntry::DirEntry(x)) => Ok(x),
-                                _ => Err(FileTreeParseError),
-                            })?
-                            .insert(name.clone(), FileTreeEntry::FileEntry(File { size }));
-                    }
-                    _ => {}
                 }
+                TerminalLine::DirectoryOutput(x) => {
+                    path.iter()
+                        .try_fold(&mut tree, |subtree, key| match subtree.get_mut(key) {
+                            Some(FileTreeEntry::DirEntry(x)) => Ok(x),
+                            _ => Err(FileTreeParseError),
+                        })
+                        .map_err(|error| ParseError::new(error).at_line(index + 1, text))?
+                        .insert(x, FileTreeEntry::DirEntry(HashMap::new()));
+                }
+                TerminalLine::FileOutput(name, size) => {
+                    path.iter()
+                        .try_fold(&mut tree, |subtree, key| match subtree.get_mut(key) {
+                            Some(FileTreeEntry::DirEntry(x)) => Ok(x),
+                            _ => Err(FileTreeParseError),
+                        })
+                        .map_err(|error| ParseError::new(error).at_line(index + 1, text))?
+                        .insert(name.clone(), FileTreeEntry::FileEntry(File { size }));
+                }
+                TerminalLine::ListInput => {}
             }
         }
         Ok(FileTree {
@@ -141,35 +156,42 @@ fn get_filetree_stats(parent: String, entry: &FileTreeEntry) -> HashMap<String,
     }
 }
 
-fn solve_first(input: &String) -> String {
-    let tree = input.parse::<FileTree>().unwrap();
-    let stats = get_filetree_stats(String::from("<root>"), &tree.value);
-    let sum: i32 = stats.values().filter(|x| x < &&100000).sum();
-    sum.to_string()
-}
+pub struct Puzzle;
 
-fn solve_second(input: &String) -> String {
-    let tree = input.parse::<FileTree>().unwrap();
-    let stats = get_filetree_stats(String::from("<root>"), &tree.value);
-    let free_space = TOTAL_SPACE - tree.value.size();
-    let needed_space = REQUIRED_SPACE - free_space;
-    let min: i32 = stats
-        .values()
-        .filter(|x| x >= &&needed_space)
-        .min()
-        .unwrap()
-        .to_owned();
-    min.to_string()
-}
+impl Solution for Puzzle {
+    type Input = FileTree;
+    type AnswerOne = i32;
+    type AnswerTwo = i32;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<FileTree>()?)
+    }
 
-pub fn solve(input: String) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+    fn part_one(tree: &FileTree) -> Result<i32, Self::Error> {
+        let stats = get_filetree_stats(String::from("<root>"), &tree.value);
+        Ok(stats.values().filter(|x| x < &&100000).sum())
+    }
+
+    fn part_two(tree: &FileTree) -> Result<i32, Self::Error> {
+        let stats = get_filetree_stats(String::from("<root>"), &tree.value);
+        let free_space = TOTAL_SPACE - tree.value.size();
+        let needed_space = REQUIRED_SPACE - free_space;
+        let min: i32 = stats
+            .values()
+            .filter(|x| x >= &&needed_space)
+            .min()
+            .ok_or(SolutionError::NoAnswer)?
+            .to_owned();
+        Ok(min)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use crate::solution::read_fixture;
 
     #[test]
     fn test_terminal_line_parse_cd() {
@@ -197,4 +219,29 @@ mod tests {
             TerminalLine::FileOutput(String::from("foo.txt"), 1234)
         );
     }
+
+    #[test]
+    fn test_file_tree_parse_unknown_line_err() {
+        let result = "$ cd /\n$ ls\ndir a\n$ rm a".parse::<FileTree>();
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(TerminalLineParseError).at_line(4, "$ rm a"))
+        );
+    }
+
+    #[test]
+    fn test_file_tree_parse_unknown_directory_err() {
+        let result = "$ cd /\n$ cd a\n$ ls\n1234 foo.txt".parse::<FileTree>();
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(FileTreeParseError).at_line(4, "1234 foo.txt"))
+        );
+    }
+
+    #[test]
+    fn test_solve_ok() {
+        let tree = Puzzle::parse(&read_fixture(7)).unwrap();
+        assert_eq!(Puzzle::part_one(&tree), Ok(95437));
+        assert_eq!(Puzzle::part_two(&tree), Ok(24933642));
+    }
 }
@@ -1,252 +1,250 @@
 use std::str::FromStr;
 
-#[derive(Debug)]
-struct MatrixParseError;
-
-#[derive(Debug)]
-struct Matrix {
-    column_count: usize,
-    row_count: usize,
-    value: Vec<u32>,
+use crate::error::{ParseError, SolutionError};
+use crate::grid::{Direction, Grid, Point};
+use crate::render::{Color, Image, Render};
+use crate::solution::Solution;
+
+// From a scenic score of 0 to the best one
+const SHADES: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+const BEST: char = 'X';
+const BEST_COLOR: Color = [255, 32, 32];
+// Pixels per tree
+const SCALE: usize = 4;
+
+#[derive(Debug, Eq, PartialEq)]
+enum MatrixParseError {
+    InvalidHeight(char),
 }
 
-#[derive(Clone, Copy, Debug)]
-struct MatrixIndex {
-    column: usize,
-    row: usize,
+impl std::fmt::Display for MatrixParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidHeight(c) => write!(f, "Invalid tree height {:?}", c),
+        }
+    }
 }
 
+pub type Matrix = Grid<u32>;
+
 trait TreehouseMap {
-    fn as_scenic_score(&self, origin: MatrixIndex) -> Option<u32>;
-    fn as_visibility(&self, origin: MatrixIndex) -> Option<bool>;
-    fn visible_from_north_south(&self, origin: MatrixIndex) -> Option<bool>;
-    fn visible_from_west_east(&self, origin: MatrixIndex) -> Option<bool>;
+    fn as_scenic_score(&self, origin: Point) -> Option<u32>;
+    fn as_visibility(&self, origin: Point) -> Option<bool>;
+    fn best_tree(&self) -> Option<(Point, u32)>;
+    fn render_image(&self) -> Image;
+    fn render_scenic_scores(&self) -> String;
+    fn render_visibility(&self) -> String;
+    fn scenic_scores(&self) -> Grid<u32>;
+    fn visibility(&self) -> Grid<bool>;
+    fn viewing_distance(&self, origin: Point, direction: Direction) -> Option<u32>;
 }
 
-impl Matrix {
-    fn cell(&self, index: MatrixIndex) -> Option<&u32> {
-        self.value.get(index.column + index.row * self.column_count)
+impl TreehouseMap for Matrix {
+    // Trees on the edge see nothing in at least one direction, and score 0
+    fn as_scenic_score(&self, origin: Point) -> Option<u32> {
+        Direction::CARDINAL
+            .iter()
+            .map(|direction| self.viewing_distance(origin, *direction))
+            .product()
     }
-    fn column(&self, index: usize) -> Option<Vec<&u32>> {
-        if self.column_count > index {
-            Some(
-                self.value
-                    .iter()
-                    .skip(index)
-                    .step_by(self.column_count)
-                    .collect(),
-            )
-        } else {
-            None
-        }
+    // Looking out from a single tree, where visibility() sweeps the whole forest
+    fn as_visibility(&self, origin: Point) -> Option<bool> {
+        let height = self.get(origin)?;
+        let visible_from = |direction| {
+            self.ray(origin, direction)
+                .all(|point| self[point] < *height)
+        };
+        Some(Direction::CARDINAL.into_iter().any(visible_from))
     }
-    // TODO: This should be Iter
-    fn indexes(&self) -> Vec<MatrixIndex> {
-        let mut result = Vec::new();
-        for row in 0..self.row_count {
-            for column in 0..self.column_count {
-                result.push(MatrixIndex { column, row })
+    // The first one in reading order when several score the same
+    fn best_tree(&self) -> Option<(Point, u32)> {
+        let mut best: Option<(Point, u32)> = None;
+        for point in self.points() {
+            let score = self.as_scenic_score(point)?;
+            if best.is_none_or(|(_, best)| score > best) {
+                best = Some((point, score));
             }
         }
-        return result;
+        best
     }
-    fn row(&self, index: usize) -> Option<Vec<&u32>> {
-        if self.row_count > index {
-            Some(
-                self.value
-                    .iter()
-                    .skip(index * self.column_count)
-                    .take(self.column_count)
-                    .collect(),
-            )
-        } else {
-            None
-        }
-    }
-}
-
-impl TreehouseMap for Matrix {
-    fn as_scenic_score(&self, origin: MatrixIndex) -> Option<u32> {
-        if origin.column == 0 || origin.column >= self.column_count - 1 {
-            return Some(0);
+    // Hidden trees in greys as dark as they are short, visible ones in greens as
+    // bright as their scenic score, and the best tree in red
+    fn render_image(&self) -> Image {
+        let visibility = self.visibility();
+        let scores = self.scenic_scores();
+        let best = self.best_tree();
+        let max = best.map_or(0, |(_, score)| score).max(1);
+
+        let mut colors = self.map(|height| {
+            let grey = (*height * 12) as u8;
+            [grey, grey, grey]
+        });
+        for (point, visible) in visibility.iter() {
+            if *visible {
+                let green = 64 + (191 * scores[point] / max) as u8;
+                colors[point] = [0, green, 0];
+            }
         }
-
-        if origin.row == 0 || origin.row >= self.row_count - 1 {
-            return Some(0);
+        if let Some((point, _)) = best {
+            colors[point] = BEST_COLOR;
         }
 
-        let height = self.cell(origin)?;
-
-        let column = self.column(origin.column)?;
-        let row = self.row(origin.row)?;
-
-        // These are reversed as we want to count outwards
-        let north = Vec::from_iter(column[0..origin.row].iter().rev().cloned());
-        let west = Vec::from_iter(row[0..origin.column].iter().rev().cloned());
-
-        let south = Vec::from_iter(column[origin.row + 1..].iter().cloned());
-        let east = Vec::from_iter(row[origin.column + 1..].iter().cloned());
-
-        let score = [north, south, west, east].iter().fold(1, |score, heights| {
-            let mut done = false;
-            let trees: u32 = heights
-                .iter()
-                .take_while(|h| {
-                    if done {
-                        return false;
-                    } else if h < &&height {
-                        return true;
-                    } else {
-                        // This one counts but next one will not
-                        done = true;
-                        return true;
-                    }
-                })
-                .count()
-                .try_into()
-                .unwrap();
-            score * trees
-        });
-
-        Some(score)
+        Image::from_grid(&colors, SCALE)
     }
-    fn as_visibility(&self, origin: MatrixIndex) -> Option<bool> {
-        if origin.column == 0 || origin.column >= self.column_count - 1 {
-            return Some(true);
+    fn render_scenic_scores(&self) -> String {
+        let scores = self.scenic_scores();
+        let best = self.best_tree();
+        let max = best.map_or(0, |(_, score)| score).max(1);
+        let steps = SHADES.len() as u32 - 1;
+
+        // Rounded up, so that only trees that see nothing are blank
+        let mut shades = scores.map(|score| SHADES[((score * steps).div_ceil(max)) as usize]);
+        if let Some((point, _)) = best {
+            shades[point] = BEST;
         }
-
-        if origin.row == 0 || origin.row >= self.row_count - 1 {
-            return Some(true);
+        shades.to_string()
+    }
+    fn render_visibility(&self) -> String {
+        let mut trees = self
+            .visibility()
+            .map(|visible| if *visible { '#' } else { '.' });
+        if let Some((point, _)) = self.best_tree() {
+            trees[point] = BEST;
         }
-
-        let north_south = self.visible_from_north_south(origin.clone());
-        let west_east = self.visible_from_west_east(origin.clone());
-
-        match (north_south, west_east) {
-            (Some(false), Some(false)) => Some(false),
-            (None, _) => None,
-            (_, None) => None,
-            _ => Some(true),
+        trees.to_string()
+    }
+    fn scenic_scores(&self) -> Grid<u32> {
+        let mut scores = self.map(|_| 0);
+        for point in self.points() {
+            scores[point] = self.as_scenic_score(point).unwrap_or_default();
         }
+        scores
     }
-    fn visible_from_north_south(&self, origin: MatrixIndex) -> Option<bool> {
-        if origin.row == 0 || origin.row >= self.row_count - 1 {
-            return Some(true);
+    // Sweeps every line from both ends, a tree being visible when taller than
+    // any before it, instead of looking out from every tree
+    fn visibility(&self) -> Grid<bool> {
+        let mut visible = self.map(|_| false);
+        let north_west = Point { row: 0, column: 0 };
+        let north_east = Point {
+            row: 0,
+            column: self.columns() - 1,
+        };
+        let south_west = Point {
+            row: self.rows() - 1,
+            column: 0,
+        };
+        let sides = [
+            (north_west, Direction::South, Direction::East),
+            (north_west, Direction::East, Direction::South),
+            (north_east, Direction::South, Direction::West),
+            (south_west, Direction::East, Direction::North),
+        ];
+
+        for (corner, along, inwards) in sides {
+            for start in self.line(corner, along) {
+                let mut tallest = None;
+                for point in self.line(start, inwards) {
+                    if tallest < Some(self[point]) {
+                        visible[point] = true;
+                        tallest = Some(self[point]);
+                    }
+                }
+            }
         }
 
-        let height = self.cell(origin)?;
-        let column = self.column(origin.column)?;
-        let (north, south) = column.split_at(origin.row);
-        let north_max = north.iter().max()?;
-        let south_max = south.iter().skip(1).max()?;
-
-        Some(north_max < &height || south_max < &height)
+        visible
     }
-    fn visible_from_west_east(&self, origin: MatrixIndex) -> Option<bool> {
-        if origin.column == 0 || origin.column >= self.column_count - 1 {
-            return Some(true);
+    // Trees up to the edge or the first one at least as tall, which counts
+    fn viewing_distance(&self, origin: Point, direction: Direction) -> Option<u32> {
+        let height = self.get(origin)?;
+        let mut trees = 0;
+        for point in self.ray(origin, direction) {
+            trees += 1;
+            if self[point] >= *height {
+                break;
+            }
         }
-
-        let height = self.cell(origin)?;
-        let row = self.row(origin.row)?;
-        let (west, east) = row.split_at(origin.column);
-        let west_max = west.iter().max()?;
-        let east_max = east.iter().skip(1).max()?;
-
-        Some(west_max < &height || east_max < &height)
+        Some(trees)
     }
 }
 
 impl FromStr for Matrix {
-    type Err = MatrixParseError;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut row_count: usize = 0;
-        let mut column_count: usize = 0;
-        let mut rows = Vec::new();
-
-        for line in s.lines() {
-            row_count += 1;
-            let mut row_column_count: usize = 0;
-            for ch in line.chars() {
-                row_column_count += 1;
-                let item = ch.to_digit(10).ok_or(MatrixParseError)?;
-                rows.push(item);
-            }
-            if column_count != 0 && column_count != row_column_count {
-                return Err(MatrixParseError);
-            } else {
-                column_count = row_column_count;
-            }
-        }
-
-        Ok(Matrix {
-            column_count,
-            row_count,
-            value: rows,
+        Grid::parse(s, |_, ch| {
+            ch.to_digit(10).ok_or(MatrixParseError::InvalidHeight(ch))
         })
     }
 }
 
-fn solve_first(input: &String) -> String {
-    let treehouse_map = input.parse::<Matrix>().unwrap();
-    let visibility: i32 = treehouse_map
-        .indexes()
-        .iter()
-        .map(|i| {
-            let visible = treehouse_map
-                .as_visibility(MatrixIndex {
-                    column: i.column,
-                    row: i.row,
-                })
-                .unwrap();
-            if visible {
-                1
-            } else {
-                0
-            }
-        })
-        .sum();
-    visibility.to_string()
-}
+pub struct Puzzle;
 
-fn solve_second(input: &String) -> String {
-    let treehouse_map = input.parse::<Matrix>().unwrap();
-    let score: u32 = treehouse_map
-        .indexes()
-        .iter()
-        .map(|i| {
-            treehouse_map
-                .as_scenic_score(MatrixIndex {
-                    column: i.column,
-                    row: i.row,
-                })
-                .unwrap()
-        })
-        .max()
-        .unwrap();
-    score.to_string()
-}
+impl Solution for Puzzle {
+    type Input = Matrix;
+    type AnswerOne = usize;
+    type AnswerTwo = u32;
+    type Error = SolutionError;
 
-pub fn solve(input: String) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<Matrix>()?)
+    }
+
+    fn part_one(treehouse_map: &Matrix) -> Result<usize, Self::Error> {
+        let visibility = treehouse_map.visibility();
+        Ok(visibility.iter().filter(|(_, visible)| **visible).count())
+    }
+
+    fn part_two(treehouse_map: &Matrix) -> Result<u32, Self::Error> {
+        let (_, score) = treehouse_map.best_tree().ok_or(SolutionError::NoAnswer)?;
+        Ok(score)
+    }
+
+    fn render(treehouse_map: &Matrix) -> Option<Render> {
+        let best = treehouse_map.best_tree();
+        let score = best.map_or(0, |(_, score)| score);
+        // A single tree, no need to sweep them all
+        let seen = match best.and_then(|(point, _)| treehouse_map.as_visibility(point)) {
+            Some(true) => "visible",
+            _ => "hidden",
+        };
+        let text = format!(
+            "Visible trees (#) and hidden ones (.), the best scenic score of {} at {}, {}\n{}\n\
+             Scenic scores, from 0 ({:?}) to {} ({:?})\n{}",
+            score,
+            BEST,
+            seen,
+            treehouse_map.render_visibility(),
+            SHADES[0],
+            score,
+            SHADES[SHADES.len() - 1],
+            treehouse_map.render_scenic_scores()
+        );
+
+        Some(Render {
+            text,
+            image: Some(treehouse_map.render_image()),
+        })
+    }
 }
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use crate::grid::GridParseError;
+    use crate::solution::read_fixture;
 
     #[test]
     fn test_matrix_row() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let row = matrix.row(0).unwrap();
+        let row: Vec<&u32> = matrix.row(0).unwrap().collect();
         assert_eq!(row, vec![&1, &2, &3]);
     }
 
     #[test]
     fn test_matrix_row_last() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let row = matrix.row(2).unwrap();
+        let row: Vec<&u32> = matrix.row(2).unwrap().collect();
         assert_eq!(row, vec![&7, &8, &9]);
     }
 
@@ -254,20 +252,20 @@ mod tests {
     fn test_matrix_row_out_of_bounds() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
         let row = matrix.row(3);
-        assert_eq!(row, None);
+        assert!(row.is_none());
     }
 
     #[test]
     fn test_matrix_column() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let column = matrix.column(0).unwrap();
+        let column: Vec<&u32> = matrix.column(0).unwrap().collect();
         assert_eq!(column, vec![&1, &4, &7]);
     }
 
     #[test]
     fn test_matrix_column_last() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let column = matrix.column(2).unwrap();
+        let column: Vec<&u32> = matrix.column(2).unwrap().collect();
         assert_eq!(column, vec![&3, &6, &9]);
     }
 
@@ -275,29 +273,29 @@ mod tests {
     fn test_matrix_column_out_of_bounds() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
         let column = matrix.column(3);
-        assert_eq!(column, None);
+        assert!(column.is_none());
     }
 
     #[test]
     fn test_matrix_cell() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let cell = matrix.cell(MatrixIndex { column: 0, row: 0 }).unwrap();
+        let cell = matrix.get(Point { column: 0, row: 0 }).unwrap();
         assert_eq!(cell, &1);
     }
 
     #[test]
     fn test_matrix_cell_last() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let cell = matrix.cell(MatrixIndex { column: 2, row: 2 }).unwrap();
+        let cell = matrix.get(Point { column: 2, row: 2 }).unwrap();
         assert_eq!(cell, &9);
     }
 
     #[test]
     fn test_matrix_cell_out_of_bounds() {
         let matrix = String::from("123\n456\n789").parse::<Matrix>().unwrap();
-        let cell_x = matrix.cell(MatrixIndex { column: 3, row: 2 });
-        let cell_y = matrix.cell(MatrixIndex { column: 2, row: 3 });
-        let cell = matrix.cell(MatrixIndex { column: 3, row: 3 });
+        let cell_x = matrix.get(Point { column: 3, row: 2 });
+        let cell_y = matrix.get(Point { column: 2, row: 3 });
+        let cell = matrix.get(Point { column: 3, row: 3 });
         assert_eq!(cell_x, None);
         assert_eq!(cell_y, None);
         assert_eq!(cell, None);
@@ -305,19 +303,11 @@ mod tests {
 
     #[test]
     fn test_treehouse_map_visibility() {
-        let matrix = String::from("30373\n25512\n65332\n33549\n35390")
-            .parse::<Matrix>()
-            .unwrap();
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
         let result: Vec<i32> = matrix
-            .indexes()
-            .iter()
+            .points()
             .map(|i| {
-                let visible = matrix
-                    .as_visibility(MatrixIndex {
-                        column: i.column,
-                        row: i.row,
-                    })
-                    .unwrap();
+                let visible = matrix.as_visibility(i).unwrap();
                 if visible {
                     1
                 } else {
@@ -339,37 +329,133 @@ mod tests {
 
     #[test]
     fn test_treehouse_map_scenic_score() {
-        let matrix = String::from("30373\n25512\n65332\n33549\n35390")
-            .parse::<Matrix>()
-            .unwrap();
-        let result_a: u32 = matrix
-            .as_scenic_score(MatrixIndex { column: 2, row: 1 })
-            .unwrap();
-        let result_b: u32 = matrix
-            .as_scenic_score(MatrixIndex { column: 2, row: 3 })
-            .unwrap();
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        let result_a: u32 = matrix.as_scenic_score(Point { column: 2, row: 1 }).unwrap();
+        let result_b: u32 = matrix.as_scenic_score(Point { column: 2, row: 3 }).unwrap();
         assert_eq!(result_a, 4);
         assert_eq!(result_b, 8);
     }
 
     #[test]
     fn test_treehouse_map_scenic_score_full() {
-        let matrix = String::from("30373\n25512\n65332\n33549\n35390")
-            .parse::<Matrix>()
-            .unwrap();
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
         let result: u32 = matrix
-            .indexes()
-            .iter()
-            .map(|i| {
-                matrix
-                    .as_scenic_score(MatrixIndex {
-                        column: i.column,
-                        row: i.row,
-                    })
-                    .unwrap()
-            })
+            .points()
+            .map(|i| matrix.as_scenic_score(i).unwrap())
             .max()
             .unwrap();
         assert_eq!(result, 8);
     }
+
+    #[test]
+    fn test_matrix_from_str_invalid_height_err() {
+        let result = "123\n4x6".parse::<Matrix>();
+        assert_eq!(
+            result.err(),
+            Some(
+                ParseError::new(MatrixParseError::InvalidHeight('x'))
+                    .at_line(2, "4x6")
+                    .at_column(2)
+            )
+        );
+    }
+
+    #[test]
+    fn test_matrix_from_str_uneven_row_err() {
+        let result = "123\n45".parse::<Matrix>();
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(GridParseError::UnevenRow).at_line(2, "45"))
+        );
+    }
+
+    #[test]
+    fn test_treehouse_map_visibility_sweep() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        let swept = matrix.visibility();
+        for point in matrix.points() {
+            assert_eq!(
+                Some(swept[point]),
+                matrix.as_visibility(point),
+                "{:?}",
+                point
+            );
+        }
+        assert_eq!(Puzzle::part_one(&matrix), Ok(21));
+    }
+
+    #[test]
+    fn test_treehouse_map_visibility_sweep_uneven_heights() {
+        let matrix = String::from("9119\n1551\n1991\n0000\n1234")
+            .parse::<Matrix>()
+            .unwrap();
+        let swept = matrix.visibility();
+        for point in matrix.points() {
+            assert_eq!(
+                Some(swept[point]),
+                matrix.as_visibility(point),
+                "{:?}",
+                point
+            );
+        }
+    }
+
+    #[test]
+    fn test_treehouse_map_out_of_bounds() {
+        let matrix = String::from("123\n456").parse::<Matrix>().unwrap();
+        let outside = Point { row: 2, column: 0 };
+        assert_eq!(matrix.as_visibility(outside), None);
+        assert_eq!(matrix.as_scenic_score(outside), None);
+    }
+
+    #[test]
+    fn test_solve_part_2_ok() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        assert_eq!(Puzzle::part_two(&matrix), Ok(8));
+    }
+
+    #[test]
+    fn test_treehouse_map_best_tree() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        assert_eq!(matrix.best_tree(), Some((Point { row: 3, column: 2 }, 8)));
+    }
+
+    #[test]
+    fn test_treehouse_map_render_visibility() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        assert_eq!(
+            matrix.render_visibility(),
+            "#####\n###.#\n##.##\n#.X.#\n#####\n"
+        );
+    }
+
+    #[test]
+    fn test_treehouse_map_render_scenic_scores() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        assert_eq!(
+            matrix.render_scenic_scores(),
+            "     \n :+: \n #:- \n :X= \n     \n"
+        );
+    }
+
+    #[test]
+    fn test_render_best_tree_visibility() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        let text = Puzzle::render(&matrix).unwrap().text;
+        assert!(text.starts_with(
+            "Visible trees (#) and hidden ones (.), the best scenic score of 8 at X, visible\n"
+        ));
+    }
+
+    #[test]
+    fn test_treehouse_map_render_image() {
+        let matrix = read_fixture(8).parse::<Matrix>().unwrap();
+        let ppm = matrix.render_image().to_ppm();
+        let header = format!("P6\n{} {}\n255\n", 5 * SCALE, 5 * SCALE);
+        assert!(ppm.starts_with(header.as_bytes()));
+        assert_eq!(ppm.len(), header.len() + 5 * 5 * SCALE * SCALE * 3);
+        // The best tree, on the fourth row of trees and the third column
+        let best = header.len() + ((3 * SCALE) * 5 * SCALE + 2 * SCALE) * 3;
+        assert_eq!(ppm[best..best + 3], BEST_COLOR);
+    }
 }
@@ -1,8 +1,19 @@
+use std::cmp::Ordering;
 use std::{collections::HashSet, str::FromStr};
 
-#[derive(Debug)]
+use crate::cancel;
+use crate::error::{parse_lines, ParseError, SolutionError};
+use crate::solution::Solution;
+
+#[derive(Debug, Eq, PartialEq)]
 struct MoveParseError;
 
+impl std::fmt::Display for MoveParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Expected one of U, D, L or R")
+    }
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
 enum Move {
     N,
@@ -15,20 +26,41 @@ enum Move {
     E,
 }
 
-#[derive(Debug)]
-struct MoveSeqParseError;
+#[derive(Debug, Eq, PartialEq)]
+enum MoveSeqParseError {
+    InvalidCount(String),
+    InvalidMove(String),
+    MissingCount,
+}
 
+impl std::fmt::Display for MoveSeqParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCount(s) => write!(f, "Invalid step count {:?}", s),
+            Self::InvalidMove(s) => write!(f, "Invalid direction {:?}, {}", s, MoveParseError),
+            Self::MissingCount => write!(f, "Expected a direction and a step count"),
+        }
+    }
+}
+
+// Kept as a count rather than repeated, the count comes straight from the input
 #[derive(Debug, PartialEq, Eq)]
 struct MoveSeq {
-    value: Vec<Move>,
+    direction: Move,
+    count: usize,
 }
 
-#[derive(Debug)]
-struct MovesParseError;
-
 #[derive(Debug, PartialEq, Eq)]
-struct Moves {
-    value: Vec<Move>,
+pub struct Moves {
+    value: Vec<MoveSeq>,
+}
+
+impl Moves {
+    fn steps(&self) -> impl Iterator<Item = Move> + '_ {
+        self.value
+            .iter()
+            .flat_map(|move_seq| std::iter::repeat_n(move_seq.direction, move_seq.count))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
@@ -60,38 +92,32 @@ impl Position {
         Position(next_x, next_y)
     }
 
-    fn apply_pull(&self, m: &Move, origin: &Position) -> Self {
+    fn apply_pull(&self, origin: &Position) -> Self {
         let distance = self.as_abs_distance(origin);
 
         if distance < 2 {
             return self.to_owned();
         }
 
-        let delta_x = (origin.0 - self.0).clamp(-1, 1);
-        let delta_y = (origin.1 - self.1).clamp(-1, 1);
-
-        self.apply_move(match (m, delta_x, delta_y) {
-            (_, -1, 1) => &Move::NW,
-            (_, 1, 1) => &Move::NE,
-            (_, -1, -1) => &Move::SW,
-            (_, 1, -1) => &Move::SE,
-            (_, 0, 1) => &Move::N,
-            (_, 0, -1) => &Move::S,
-            (_, -1, 0) => &Move::W,
-            (_, 1, 0) => &Move::E,
-            _ => panic!("Argh"),
+        self.apply_move(match (origin.0.cmp(&self.0), origin.1.cmp(&self.1)) {
+            (Ordering::Less, Ordering::Greater) => &Move::NW,
+            (Ordering::Greater, Ordering::Greater) => &Move::NE,
+            (Ordering::Less, Ordering::Less) => &Move::SW,
+            (Ordering::Greater, Ordering::Less) => &Move::SE,
+            (Ordering::Equal, Ordering::Greater) => &Move::N,
+            (Ordering::Equal, Ordering::Less) => &Move::S,
+            (Ordering::Less, Ordering::Equal) => &Move::W,
+            (Ordering::Greater, Ordering::Equal) => &Move::E,
+            // On top of each other, too close to be pulled
+            (Ordering::Equal, Ordering::Equal) => return self.to_owned(),
         })
     }
 
-    fn as_abs_distance(&self, origin: &Position) -> i32 {
+    fn as_abs_distance(&self, origin: &Position) -> u32 {
         let distance_x = origin.0.abs_diff(self.0);
         let distance_y = origin.1.abs_diff(self.1);
 
-        if distance_x > distance_y {
-            distance_x.try_into().unwrap()
-        } else {
-            distance_y.try_into().unwrap()
-        }
+        distance_x.max(distance_y)
     }
 }
 
@@ -105,7 +131,7 @@ impl Rope {
     fn new(length: usize) -> Self {
         Rope {
             head: Position::new(),
-            tail: std::iter::repeat(Position::new()).take(length).collect(),
+            tail: std::iter::repeat_n(Position::new(), length).collect(),
         }
     }
 
@@ -114,20 +140,17 @@ impl Rope {
         let mut tail = Vec::new();
 
         for position in self.tail.iter() {
-            let prev_knot = if tail.len() == 0 {
-                head
-            } else {
-                tail.last().cloned().unwrap()
-            };
-            let next_position = position.apply_pull(m, &prev_knot);
+            let prev_knot = tail.last().cloned().unwrap_or(head);
+            let next_position = position.apply_pull(&prev_knot);
             tail.push(next_position);
         }
 
         Rope { head, tail }
     }
 
-    fn as_tail_position(&self) -> Option<Position> {
-        self.tail.iter().last().cloned()
+    // A rope without knots behind its head ends at the head
+    fn as_tail_position(&self) -> Position {
+        self.tail.last().cloned().unwrap_or(self.head)
     }
 }
 
@@ -184,73 +207,76 @@ impl FromStr for Move {
 impl FromStr for MoveSeq {
     type Err = MoveSeqParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (move_type, count) = s.split_once(" ").ok_or(MoveSeqParseError)?;
-
-        let move_type = move_type.parse::<Move>().map_err(|_| MoveSeqParseError)?;
-        let count = count.parse::<usize>().map_err(|_| MoveSeqParseError)?;
-
-        let value = std::iter::repeat(move_type).take(count).collect();
-
-        Ok(MoveSeq { value })
+        let (move_type, count) = s.split_once(' ').ok_or(MoveSeqParseError::MissingCount)?;
+
+        let move_type = move_type
+            .parse::<Move>()
+            .map_err(|_| MoveSeqParseError::InvalidMove(move_type.to_string()))?;
+        let count = count
+            .parse::<usize>()
+            .map_err(|_| MoveSeqParseError::InvalidCount(count.to_string()))?;
+
+        Ok(MoveSeq {
+            direction: move_type,
+            count,
+        })
     }
 }
 
 impl FromStr for Moves {
-    type Err = MovesParseError;
+    type Err = ParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let value = s
-            .lines()
-            .flat_map(|line| line.parse::<MoveSeq>())
-            .flat_map(|move_seq| move_seq.value)
-            .collect();
+        let value = parse_lines::<MoveSeq>(s)?;
 
         Ok(Moves { value })
     }
 }
 
-fn solve_first(input: &String) -> String {
-    let moves = input.parse::<Moves>().unwrap();
-    let mut rope = Rope::new(1);
+fn count_tail_positions(moves: &Moves, length: usize) -> usize {
+    let mut rope = Rope::new(length);
     let mut tail_moves = HashSet::new();
-    for m in moves.value {
+    // A single line can ask for more steps than there is time for
+    for m in moves.steps().take_while(|_| !cancel::cancelled()) {
         let next_rope = rope.apply_move(&m);
-        tail_moves.insert(next_rope.as_tail_position().unwrap());
+        tail_moves.insert(next_rope.as_tail_position());
         rope = next_rope;
     }
-    tail_moves.len().to_string()
+    tail_moves.len()
 }
 
-fn solve_second(input: &String) -> String {
-    let moves = input.parse::<Moves>().unwrap();
-    let mut rope = Rope::new(9);
-    let mut tail_moves = HashSet::new();
-    for m in moves.value {
-        let next_rope = rope.apply_move(&m);
-        tail_moves.insert(next_rope.as_tail_position().unwrap());
-        rope = next_rope;
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    type Input = Moves;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+        Ok(input.parse::<Moves>()?)
     }
-    tail_moves.len().to_string()
-}
 
-pub fn solve(input: String) -> (String, String) {
-    (solve_first(&input), solve_second(&input))
+    fn part_one(moves: &Moves) -> Result<usize, Self::Error> {
+        Ok(count_tail_positions(moves, 1))
+    }
+
+    fn part_two(moves: &Moves) -> Result<usize, Self::Error> {
+        Ok(count_tail_positions(moves, 9))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
-
-    // "R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2"
+    use crate::solution::read_fixture;
 
     #[test]
     fn test_moves_from_str() {
         let moves = "U 1\nD 1\nL 1\nR 1".parse::<Moves>().unwrap();
         assert_eq!(
-            moves,
-            Moves {
-                value: vec![Move::N, Move::S, Move::W, Move::E]
-            }
+            moves.steps().collect::<Vec<_>>(),
+            vec![Move::N, Move::S, Move::W, Move::E]
         );
     }
 
@@ -258,33 +284,60 @@ mod tests {
     fn test_moves_from_str_repeating() {
         let moves = "U 2\nD 3\nL 1\nR 4".parse::<Moves>().unwrap();
         assert_eq!(
-            moves,
-            Moves {
-                value: vec![
-                    Move::N,
-                    Move::N,
-                    Move::S,
-                    Move::S,
-                    Move::S,
-                    Move::W,
-                    Move::E,
-                    Move::E,
-                    Move::E,
-                    Move::E
-                ]
-            }
+            moves.steps().collect::<Vec<_>>(),
+            vec![
+                Move::N,
+                Move::N,
+                Move::S,
+                Move::S,
+                Move::S,
+                Move::W,
+                Move::E,
+                Move::E,
+                Move::E,
+                Move::E
+            ]
+        );
+    }
+
+    #[test]
+    fn test_moves_from_str_err() {
+        let result = "U 1\nD x\nL 1".parse::<Moves>();
+        assert_eq!(
+            result,
+            Err(
+                ParseError::new(MoveSeqParseError::InvalidCount(String::from("x")))
+                    .at_line(2, "D x")
+            )
+        );
+    }
+
+    #[test]
+    fn test_moves_from_str_huge_count() {
+        let moves = "R 99999999999".parse::<Moves>().unwrap();
+        assert_eq!(moves.steps().take(3).count(), 3);
+        assert_eq!(
+            moves.value,
+            vec![MoveSeq {
+                direction: Move::E,
+                count: 99999999999
+            }]
+        );
+    }
+
+    #[test]
+    fn test_move_seq_from_str_invalid_move_err() {
+        let result = "X 1".parse::<MoveSeq>();
+        assert_eq!(
+            result,
+            Err(MoveSeqParseError::InvalidMove(String::from("X")))
         );
     }
 
     #[test]
     fn test_position_apply_move() {
-        let moves = Moves {
-            value: vec![Move::N, Move::N, Move::W, Move::W],
-        };
-        let position = moves
-            .value
-            .iter()
-            .fold(Position::new(), |p, m| p.apply_move(m));
+        let moves = [Move::N, Move::N, Move::W, Move::W];
+        let position = moves.iter().fold(Position::new(), |p, m| p.apply_move(m));
 
         assert_eq!(position, Position(-2, 2));
     }
@@ -301,10 +354,7 @@ mod tests {
     #[test]
     fn test_rope_apply_move_1() {
         let moves = "U 4\nR 4".parse::<Moves>().unwrap();
-        let rope = moves
-            .value
-            .iter()
-            .fold(Rope::new(4), |r, m| r.apply_move(m));
+        let rope = moves.steps().fold(Rope::new(4), |r, m| r.apply_move(&m));
 
         let expected = Rope {
             head: Position(4, 4),
@@ -322,10 +372,7 @@ mod tests {
     #[test]
     fn test_rope_apply_move_2() {
         let moves = "R 5\nU 8".parse::<Moves>().unwrap();
-        let rope = moves
-            .value
-            .iter()
-            .fold(Rope::new(4), |r, m| r.apply_move(m));
+        let rope = moves.steps().fold(Rope::new(4), |r, m| r.apply_move(&m));
 
         let expected = Rope {
             head: Position(5, 8),
@@ -343,10 +390,7 @@ mod tests {
     #[test]
     fn test_rope_apply_move_3() {
         let moves = "R 5\nU 8\nL 5".parse::<Moves>().unwrap();
-        let rope = moves
-            .value
-            .iter()
-            .fold(Rope::new(4), |r, m| r.apply_move(m));
+        let rope = moves.steps().fold(Rope::new(4), |r, m| r.apply_move(&m));
 
         let expected = Rope {
             head: Position(0, 8),
@@ -364,10 +408,7 @@ mod tests {
     #[test]
     fn test_rope_apply_move_4() {
         let moves = "U 4\nR 5\nD 2".parse::<Moves>().unwrap();
-        let rope = moves
-            .value
-            .iter()
-            .fold(Rope::new(4), |r, m| r.apply_move(m));
+        let rope = moves.steps().fold(Rope::new(4), |r, m| r.apply_move(&m));
 
         let expected = Rope {
             head: Position(5, 2),
@@ -382,27 +423,39 @@ mod tests {
         assert_eq!(rope, expected);
     }
 
+    #[test]
+    fn test_position_apply_pull_same_position() {
+        let position = Position(2, 3);
+        assert_eq!(position.apply_pull(&Position(2, 3)), position);
+    }
+
+    #[test]
+    fn test_rope_without_tail() {
+        let rope = Rope::new(0).apply_move(&Move::N);
+        assert_eq!(rope.as_tail_position(), Position(0, 1));
+    }
+
     #[test]
     fn test_solution_1() {
-        let input = String::from("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2");
-        let result = solve_first(&input);
+        let input = Puzzle::parse(&read_fixture(9)).unwrap();
+        let result = Puzzle::part_one(&input).unwrap();
 
-        assert_eq!(result, "13");
+        assert_eq!(result, 13);
     }
 
     #[test]
     fn test_solution_2_short() {
-        let input = String::from("R 4\nU 4\nL 3\nD 1\nR 4\nD 1\nL 5\nR 2");
-        let result = solve_second(&input);
+        let input = Puzzle::parse(&read_fixture(9)).unwrap();
+        let result = Puzzle::part_two(&input).unwrap();
 
-        assert_eq!(result, "1");
+        assert_eq!(result, 1);
     }
 
     #[test]
     fn test_solution_2_long() {
-        let input = String::from("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20");
-        let result = solve_second(&input);
+        let input = Puzzle::parse("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20").unwrap();
+        let result = Puzzle::part_two(&input).unwrap();
 
-        assert_eq!(result, "36");
+        assert_eq!(result, 36);
     }
 }
@@ -0,0 +1,365 @@
+use std::io;
+use std::path::PathBuf;
+
+use crate::bench::BaselineError;
+use crate::cache::Poison;
+use crate::config::ConfigError;
+use crate::guard::Panic;
+use crate::ledger::LedgerError;
+use crate::puzzle::PuzzleError;
+use crate::scaffold::ScaffoldError;
+use crate::solution::Part;
+use crate::submit::SubmitError;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub text: Option<String>,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(reason: impl ToString) -> Self {
+        ParseError {
+            line: None,
+            column: None,
+            text: None,
+            reason: reason.to_string(),
+        }
+    }
+
+    // Lines are counted from 1, like in any text editor
+    pub fn at_line(mut self, line: usize, text: &str) -> Self {
+        self.line = Some(line);
+        self.text = Some(text.to_string());
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    // For blocks parsed on their own, after the lines before them
+    pub fn after(mut self, lines: usize) -> Self {
+        self.line = self.line.map(|line| line + lines);
+        self
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: ", line, column)?,
+            (Some(line), None) => write!(f, "line {}: ", line)?,
+            _ => {}
+        };
+
+        write!(f, "{}", self.reason)?;
+
+        if let Some(text) = &self.text {
+            write!(f, " in {:?}", text)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// Parses every line on its own, reporting the first line that fails
+pub fn parse_lines<T>(input: &str) -> Result<Vec<T>, ParseError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.parse::<T>()
+                .map_err(|error| ParseError::new(error).at_line(index + 1, line))
+        })
+        .collect()
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum SolutionError {
+    NoAnswer,
+    Panic(Panic),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoAnswer => write!(f, "No answer found"),
+            Self::Panic(panic) => write!(f, "{}", panic),
+            Self::Parse(_) => write!(f, "Unable to parse input"),
+        }
+    }
+}
+
+impl std::error::Error for SolutionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoAnswer | Self::Panic(_) => None,
+            Self::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<ParseError> for SolutionError {
+    fn from(error: ParseError) -> Self {
+        SolutionError::Parse(error)
+    }
+}
+
+impl From<std::convert::Infallible> for SolutionError {
+    fn from(error: std::convert::Infallible) -> Self {
+        match error {}
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Baseline {
+        source: BaselineError,
+    },
+    Cache {
+        day: u8,
+        source: io::Error,
+    },
+    Config {
+        source: ConfigError,
+    },
+    Expected {
+        day: u8,
+        source: ParseError,
+    },
+    Fetch {
+        day: u8,
+        source: Box<ureq::Error>,
+    },
+    Image {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Input {
+        day: u8,
+        path: Option<PathBuf>,
+        source: io::Error,
+    },
+    Ledger {
+        source: LedgerError,
+    },
+    Locked {
+        day: u8,
+        remaining: std::time::Duration,
+    },
+    Puzzle {
+        day: u8,
+        source: PuzzleError,
+    },
+    Rejected {
+        day: u8,
+        source: Poison,
+    },
+    Scaffold {
+        day: u8,
+        source: ScaffoldError,
+    },
+    Solution {
+        day: u8,
+        part: Option<Part>,
+        source: SolutionError,
+    },
+    Submit {
+        day: u8,
+        part: Part,
+        source: SubmitError,
+    },
+    TimedOut {
+        day: u8,
+        part: Option<Part>,
+        elapsed: std::time::Duration,
+    },
+}
+
+impl Error {
+    // Renders the error along with every error that caused it
+    pub fn report(&self) -> String {
+        let mut lines = vec![self.to_string()];
+        let mut source = std::error::Error::source(self);
+
+        while let Some(error) = source {
+            lines.push(format!("caused by: {}", error));
+            source = error.source();
+        }
+
+        lines.join("\n")
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Baseline { .. } => write!(f, "Failed to use the benchmark baseline"),
+            Self::Cache { day, .. } => write!(f, "Failed to access cached input for day {}", day),
+            Self::Config { .. } => write!(f, "Invalid configuration"),
+            Self::Expected { day, .. } => {
+                write!(f, "Invalid expected answers for day {}", day)
+            }
+            Self::Fetch { day, .. } => write!(f, "Failed to fetch input for day {}", day),
+            Self::Image { path, .. } => {
+                write!(f, "Failed to save the image to {}", path.display())
+            }
+            Self::Input {
+                day,
+                path: Some(path),
+                ..
+            } => write!(
+                f,
+                "Failed to read input for day {} from {}",
+                day,
+                path.display()
+            ),
+            Self::Input {
+                day, path: None, ..
+            } => write!(f, "Failed to read input for day {} from stdin", day),
+            Self::Ledger { .. } => write!(f, "Failed to use the answer ledger"),
+            Self::Locked { day, remaining } => write!(
+                f,
+                "Day {} is still locked, it unlocks in {}",
+                day,
+                crate::schedule::format_duration(*remaining)
+            ),
+            Self::Puzzle { day, .. } => write!(f, "Failed to get the example of day {}", day),
+            Self::Rejected { day, .. } => {
+                write!(f, "Refusing the input fetched for day {}", day)
+            }
+            Self::Scaffold { day, .. } => write!(f, "Failed to add day {}", day),
+            Self::Solution {
+                day,
+                part: Some(part),
+                ..
+            } => write!(f, "Failed to solve day {}, part {}", day, part),
+            Self::Solution {
+                day, part: None, ..
+            } => write!(f, "Failed to solve day {}", day),
+            Self::Submit { day, part, .. } => {
+                write!(f, "Failed to submit day {}, part {}", day, part)
+            }
+            Self::TimedOut {
+                day,
+                part: Some(part),
+                elapsed,
+            } => write!(
+                f,
+                "Gave up on day {}, part {} after {:.2?}",
+                day, part, elapsed
+            ),
+            Self::TimedOut {
+                day,
+                part: None,
+                elapsed,
+            } => write!(f, "Gave up parsing day {} after {:.2?}", day, elapsed),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Baseline { source } => Some(source),
+            Self::Cache { source, .. } => Some(source),
+            Self::Config { source } => Some(source),
+            Self::Expected { source, .. } => Some(source),
+            Self::Fetch { source, .. } => Some(source.as_ref()),
+            Self::Image { source, .. } => Some(source),
+            Self::Input { source, .. } => Some(source),
+            Self::Ledger { source } => Some(source),
+            Self::Locked { .. } => None,
+            Self::Puzzle { source, .. } => Some(source),
+            Self::Rejected { source, .. } => Some(source),
+            Self::Scaffold { source, .. } => Some(source),
+            Self::Solution { source, .. } => Some(source),
+            Self::Submit { source, .. } => Some(source),
+            Self::TimedOut { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_display() {
+        let error = ParseError::new("invalid move").at_line(3, "R X");
+        assert_eq!(error.to_string(), "line 3: invalid move in \"R X\"");
+    }
+
+    #[test]
+    fn test_parse_error_display_column() {
+        let error = ParseError::new("invalid height")
+            .at_line(2, "12a4")
+            .at_column(3);
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: invalid height in \"12a4\""
+        );
+    }
+
+    #[test]
+    fn test_parse_error_after() {
+        let error = parse_lines::<i32>("1\nBOOM").unwrap_err().after(4);
+        assert_eq!(
+            error,
+            ParseError::new("invalid digit found in string").at_line(6, "BOOM")
+        );
+        assert_eq!(ParseError::new("empty").after(4).line, None);
+    }
+
+    #[test]
+    fn test_parse_lines_ok() {
+        let result = parse_lines::<i32>("1\n2\n3\n");
+        assert_eq!(result, Ok(vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn test_parse_lines_err() {
+        let result = parse_lines::<i32>("1\nBOOM\n3");
+        assert_eq!(
+            result,
+            Err(ParseError::new("invalid digit found in string").at_line(2, "BOOM"))
+        );
+    }
+
+    #[test]
+    fn test_error_report() {
+        let error = Error::Solution {
+            day: 7,
+            part: None,
+            source: SolutionError::Parse(ParseError::new("unknown command").at_line(1, "$ rm")),
+        };
+        assert_eq!(
+            error.report(),
+            "Failed to solve day 7\ncaused by: Unable to parse input\ncaused by: line 1: unknown command in \"$ rm\""
+        );
+    }
+
+    #[test]
+    fn test_error_report_no_answer() {
+        let error = Error::Solution {
+            day: 12,
+            part: Some(Part::Two),
+            source: SolutionError::NoAnswer,
+        };
+        assert_eq!(
+            error.report(),
+            "Failed to solve day 12, part 2\ncaused by: No answer found"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::error::{Error, ParseError};
+use crate::input::FIXTUREDIR;
+use crate::solution::Part;
+
+// Expected answers for a fixture, one "P: answer" per part:
+//
+//     1: 24000
+//     2: 45000
+//
+// Lines that don't start a part carry on the previous answer, for multi-line answers
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Expected {
+    one: Option<String>,
+    two: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum ExpectedParseError {
+    DuplicatePart(Part),
+    MissingPart,
+}
+
+impl std::fmt::Display for ExpectedParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicatePart(part) => write!(f, "Part {} is expected twice", part),
+            Self::MissingPart => write!(f, "Expected \"1: answer\" or \"2: answer\""),
+        }
+    }
+}
+
+impl Expected {
+    pub fn get(&self, part: Part) -> Option<&str> {
+        match part {
+            Part::One => self.one.as_deref(),
+            Part::Two => self.two.as_deref(),
+        }
+    }
+
+    fn get_mut(&mut self, part: Part) -> &mut Option<String> {
+        match part {
+            Part::One => &mut self.one,
+            Part::Two => &mut self.two,
+        }
+    }
+}
+
+impl FromStr for Expected {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut expected = Expected::default();
+        let mut current = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let at_line = |error| ParseError::new(error).at_line(index + 1, line);
+            let start = line
+                .split_once(':')
+                .and_then(|(part, answer)| Some((part.parse::<Part>().ok()?, answer.trim())));
+
+            match (start, current) {
+                (Some((part, answer)), _) => {
+                    let slot = expected.get_mut(part);
+                    if slot.is_some() {
+                        return Err(at_line(ExpectedParseError::DuplicatePart(part)));
+                    }
+                    *slot = Some(answer.to_string());
+                    current = Some(part);
+                }
+                (None, Some(part)) => {
+                    if let Some(answer) = expected.get_mut(part) {
+                        if !answer.is_empty() {
+                            answer.push('\n');
+                        }
+                        answer.push_str(line);
+                    }
+                }
+                (None, None) if line.trim().is_empty() => {}
+                (None, None) => return Err(at_line(ExpectedParseError::MissingPart)),
+            }
+        }
+
+        Ok(expected)
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+}
+
+impl Verdict {
+    // Trailing whitespace is not part of the answer
+    pub fn check(expected: &str, answer: &str) -> Self {
+        if expected.trim_end() == answer.trim_end() {
+            Verdict::Pass
+        } else {
+            Verdict::Fail(expected.trim_end().to_string())
+        }
+    }
+}
+
+pub fn expected_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("{}/day_{}.expected", FIXTUREDIR, day))
+}
+
+// Fixtures don't need expected answers, the runner then just prints them
+pub fn read_expected(day: u8) -> Result<Option<Expected>, Error> {
+    let path = expected_path(day);
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => {
+            return Err(Error::Input {
+                day,
+                path: Some(path),
+                source,
+            })
+        }
+    };
+
+    content
+        .parse::<Expected>()
+        .map(Some)
+        .map_err(|source| Error::Expected { day, source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_from_str_ok() {
+        let result = "1: 24000\n2: 45000\n".parse::<Expected>().unwrap();
+        assert_eq!(result.get(Part::One), Some("24000"));
+        assert_eq!(result.get(Part::Two), Some("45000"));
+    }
+
+    #[test]
+    fn test_expected_from_str_multi_line() {
+        let result = "2:\n##..\n..##\n".parse::<Expected>().unwrap();
+        assert_eq!(result.get(Part::One), None);
+        assert_eq!(result.get(Part::Two), Some("##..\n..##"));
+    }
+
+    #[test]
+    fn test_expected_from_str_missing_part_err() {
+        let result = "24000".parse::<Expected>();
+        assert_eq!(
+            result,
+            Err(ParseError::new(ExpectedParseError::MissingPart).at_line(1, "24000"))
+        );
+    }
+
+    #[test]
+    fn test_expected_from_str_duplicate_part_err() {
+        let result = "1: 1\n1: 2".parse::<Expected>();
+        assert_eq!(
+            result,
+            Err(ParseError::new(ExpectedParseError::DuplicatePart(Part::One)).at_line(2, "1: 2"))
+        );
+    }
+
+    #[test]
+    fn test_verdict_check() {
+        assert_eq!(Verdict::check("31", "31"), Verdict::Pass);
+        assert_eq!(
+            Verdict::check("31\n", "32"),
+            Verdict::Fail(String::from("31"))
+        );
+    }
+
+    #[test]
+    fn test_read_expected_fixtures() {
+        let expected = read_expected(12).unwrap().unwrap();
+        assert_eq!(expected.get(Part::One), Some("31"));
+        let expected = read_expected(10).unwrap().unwrap();
+        assert!(expected
+            .get(Part::Two)
+            .unwrap()
+            .starts_with("##..##..##..##..##"));
+        assert_eq!(expected.get(Part::Two).unwrap().lines().count(), 6);
+        assert!(read_expected(25).unwrap().is_none());
+    }
+}
@@ -0,0 +1,426 @@
+// Shared by the days laid out on a map
+
+use std::ops::{Index, IndexMut};
+
+use crate::error::ParseError;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum GridParseError {
+    Empty,
+    UnevenRow,
+}
+
+impl std::fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Empty grid"),
+            Self::UnevenRow => write!(f, "Row length differs from the first row"),
+        }
+    }
+}
+
+// Rows grow southwards and columns eastwards, like lines and characters of the input.
+// No day walks the diagonals yet
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Direction {
+    East,
+    North,
+    NorthEast,
+    NorthWest,
+    South,
+    SouthEast,
+    SouthWest,
+    West,
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+    #[allow(dead_code)]
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::NorthEast,
+        Direction::East,
+        Direction::SouthEast,
+        Direction::South,
+        Direction::SouthWest,
+        Direction::West,
+        Direction::NorthWest,
+    ];
+
+    // As (columns, rows)
+    fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::East => (1, 0),
+            Direction::North => (0, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::NorthWest => (-1, -1),
+            Direction::South => (0, 1),
+            Direction::SouthEast => (1, 1),
+            Direction::SouthWest => (-1, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+}
+
+// Ordered row first, so that sorting points gives the reading order
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Point {
+    // None when stepping off the top or left edge, the grid checks the others
+    pub fn step(self, direction: Direction) -> Option<Point> {
+        let (columns, rows) = direction.offset();
+        Some(Point {
+            row: self.row.checked_add_signed(rows)?,
+            column: self.column.checked_add_signed(columns)?,
+        })
+    }
+
+    // Steps between two points, without diagonals
+    pub fn manhattan(self, other: Point) -> usize {
+        self.row.abs_diff(other.row) + self.column.abs_diff(other.column)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    columns: usize,
+    rows: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    // One row per line and one cell per character, every row as long as the first
+    pub fn parse<F, E>(s: &str, mut cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(Point, char) -> Result<T, E>,
+        E: ToString,
+    {
+        let mut columns = None;
+        let mut rows = 0;
+        let mut cells = Vec::new();
+
+        for (row, line) in s.lines().enumerate() {
+            let start = cells.len();
+            for (column, ch) in line.chars().enumerate() {
+                let value = cell(Point { row, column }, ch).map_err(|error| {
+                    ParseError::new(error)
+                        .at_line(row + 1, line)
+                        .at_column(column + 1)
+                })?;
+                cells.push(value);
+            }
+
+            let length = cells.len() - start;
+            if *columns.get_or_insert(length) != length {
+                return Err(ParseError::new(GridParseError::UnevenRow).at_line(row + 1, line));
+            }
+            rows += 1;
+        }
+
+        match columns {
+            Some(columns) if columns > 0 => Ok(Grid {
+                columns,
+                rows,
+                cells,
+            }),
+            _ => Err(ParseError::new(GridParseError::Empty)),
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.column < self.columns && point.row < self.rows
+    }
+
+    fn offset(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then_some(point.column + point.row * self.columns)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.offset(point).map(|offset| &self.cells[offset])
+    }
+
+    // Every point, in reading order
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |row| (0..columns).map(move |column| Point { row, column }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    // Points from the origin, included, to the edge of the grid
+    pub fn line(&self, origin: Point, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(origin), move |point| point.step(direction))
+            .take_while(|point| self.contains(*point))
+    }
+
+    // Points from the origin, excluded, to the edge of the grid
+    pub fn ray(&self, origin: Point, direction: Direction) -> impl Iterator<Item = Point> + '_ {
+        self.line(origin, direction).skip(1)
+    }
+
+    fn around<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Direction],
+    ) -> impl Iterator<Item = Point> + 'a {
+        directions
+            .iter()
+            .filter_map(move |direction| point.step(*direction))
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    // North, east, south and west of the point, when in the grid
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.around(point, &Direction::CARDINAL)
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            columns: self.columns,
+            rows: self.rows,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+// Asked for along with the rest, though no day needs them yet
+#[allow(dead_code)]
+impl<T> Grid<T> {
+    pub fn row(&self, row: usize) -> Option<impl Iterator<Item = &T>> {
+        (row < self.rows).then(|| self.cells[row * self.columns..(row + 1) * self.columns].iter())
+    }
+
+    pub fn column(&self, column: usize) -> Option<impl Iterator<Item = &T>> {
+        (column < self.columns).then(|| self.cells.iter().skip(column).step_by(self.columns))
+    }
+
+    // Neighbours and diagonals, clockwise from the north, when in the grid
+    pub fn neighbours_with_diagonals(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.around(point, &Direction::ALL)
+    }
+
+    // Builds a grid of the given size, each cell taken from a point of this one
+    fn remap(&self, columns: usize, rows: usize, source: impl Fn(Point) -> Point) -> Self
+    where
+        T: Clone,
+    {
+        let shape = Grid {
+            columns,
+            rows,
+            cells: Vec::new(),
+        };
+        let cells = shape
+            .points()
+            .map(|point| self[source(point)].clone())
+            .collect();
+        Grid { cells, ..shape }
+    }
+
+    // Rows become columns
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.rows, self.columns, |point| Point {
+            row: point.column,
+            column: point.row,
+        })
+    }
+
+    // A quarter turn clockwise, the first column becoming the first row reversed
+    pub fn rotate(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.remap(self.rows, self.columns, |point| Point {
+            row: self.rows - 1 - point.column,
+            column: point.row,
+        })
+    }
+}
+
+// Out of the grid is a bug of the caller, get() is there for the other cases
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+    fn index(&self, point: Point) -> &T {
+        match self.get(point) {
+            Some(value) => value,
+            None => panic!(
+                "{:?} out of a grid of {} columns and {} rows",
+                point, self.columns, self.rows
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (columns, rows) = (self.columns, self.rows);
+        match self.offset(point) {
+            Some(offset) => &mut self.cells[offset],
+            None => panic!(
+                "{:?} out of a grid of {} columns and {} rows",
+                point, columns, rows
+            ),
+        }
+    }
+}
+
+// Cells are right-aligned and, when wider than a character, separated by spaces
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cells: Vec<String> = self.cells.iter().map(|cell| cell.to_string()).collect();
+        let width = cells
+            .iter()
+            .map(|cell| cell.chars().count())
+            .max()
+            .unwrap_or(0);
+        let separator = if width > 1 { " " } else { "" };
+
+        for row in cells.chunks(self.columns.max(1)) {
+            let row: Vec<String> = row
+                .iter()
+                .map(|cell| format!("{:>width$}", cell, width = width))
+                .collect();
+            writeln!(f, "{}", row.join(separator))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(s: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(s, |_, ch| ch.to_digit(10).ok_or("Not a digit"))
+    }
+
+    #[test]
+    fn test_grid_parse() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.columns(), 3);
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid[Point { row: 1, column: 0 }], 4);
+    }
+
+    #[test]
+    fn test_grid_parse_err() {
+        assert_eq!(
+            digits("123\n4x6").unwrap_err(),
+            ParseError::new("Not a digit")
+                .at_line(2, "4x6")
+                .at_column(2)
+        );
+        assert_eq!(
+            digits("123\n45").unwrap_err(),
+            ParseError::new(GridParseError::UnevenRow).at_line(2, "45")
+        );
+        assert_eq!(
+            digits("").unwrap_err(),
+            ParseError::new(GridParseError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_grid_get_out_of_bounds() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.get(Point { row: 0, column: 3 }), None);
+        assert_eq!(grid.get(Point { row: 2, column: 0 }), None);
+        assert_eq!(grid.get(Point { row: 1, column: 2 }), Some(&6));
+    }
+
+    #[test]
+    fn test_grid_row_and_column() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.row(1).unwrap().collect::<Vec<_>>(), vec![&4, &5, &6]);
+        assert_eq!(grid.column(2).unwrap().collect::<Vec<_>>(), vec![&3, &6]);
+        assert!(grid.row(2).is_none());
+        assert!(grid.column(3).is_none());
+    }
+
+    #[test]
+    fn test_grid_ray() {
+        let grid = digits("123\n456\n789").unwrap();
+        let center = Point { row: 1, column: 1 };
+        let values = |direction| {
+            grid.ray(center, direction)
+                .map(|point| grid[point])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(Direction::North), vec![2]);
+        assert_eq!(values(Direction::SouthEast), vec![9]);
+        let corner = Point { row: 0, column: 0 };
+        assert_eq!(
+            grid.ray(corner, Direction::East).collect::<Vec<_>>(),
+            vec![Point { row: 0, column: 1 }, Point { row: 0, column: 2 }]
+        );
+        assert_eq!(grid.ray(corner, Direction::West).count(), 0);
+        assert_eq!(grid.line(corner, Direction::South).count(), 3);
+        assert_eq!(grid.line(corner, Direction::West).count(), 1);
+    }
+
+    #[test]
+    fn test_grid_neighbours() {
+        let grid = digits("123\n456\n789").unwrap();
+        let corner = Point { row: 0, column: 0 };
+        let values = |points: Vec<Point>| points.into_iter().map(|point| grid[point]).collect();
+        let result: Vec<u32> = values(grid.neighbours(corner).collect());
+        assert_eq!(result, vec![2, 4]);
+        let result: Vec<u32> = values(grid.neighbours_with_diagonals(corner).collect());
+        assert_eq!(result, vec![2, 5, 4]);
+        let center = Point { row: 1, column: 1 };
+        assert_eq!(grid.neighbours_with_diagonals(center).count(), 8);
+    }
+
+    #[test]
+    fn test_grid_transpose_and_rotate() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.transpose(), digits("14\n25\n36").unwrap());
+        assert_eq!(grid.rotate(), digits("41\n52\n63").unwrap());
+        assert_eq!(grid.rotate().rotate().rotate().rotate(), grid);
+    }
+
+    #[test]
+    fn test_point_manhattan() {
+        let a = Point { row: 1, column: 4 };
+        let b = Point { row: 3, column: 0 };
+        assert_eq!(a.manhattan(b), 6);
+        assert_eq!(b.manhattan(a), 6);
+    }
+
+    #[test]
+    fn test_grid_display() {
+        let grid = digits("123\n456").unwrap();
+        assert_eq!(grid.to_string(), "123\n456\n");
+        assert_eq!(
+            grid.map(|cell| cell * 4).to_string(),
+            " 4  8 12\n16 20 24\n"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Panic {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl std::fmt::Display for Panic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "Panicked at {}: {}", location, self.message),
+            None => write!(f, "Panicked: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for Panic {}
+
+thread_local! {
+    static GUARDED: Cell<bool> = const { Cell::new(false) };
+    static CAUGHT: RefCell<Option<Panic>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+// Panics in guarded code are kept for the caller instead of being printed,
+// any other panic goes through the usual hook
+fn install_hook() {
+    HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !GUARDED.with(Cell::get) {
+                return default(info);
+            }
+
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("Box<dyn Any>"));
+            let location = info.location().map(|location| {
+                format!(
+                    "{}:{}:{}",
+                    location.file(),
+                    location.line(),
+                    location.column()
+                )
+            });
+
+            CAUGHT.with(|caught| *caught.borrow_mut() = Some(Panic { message, location }));
+        }));
+    });
+}
+
+// Runs f, turning a panic into an error so that the caller can carry on
+pub fn catch<R>(f: impl FnOnce() -> R) -> Result<R, Panic> {
+    install_hook();
+
+    let outer = GUARDED.with(|guarded| guarded.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    GUARDED.with(|guarded| guarded.set(outer));
+
+    result.map_err(|_| {
+        CAUGHT
+            .with(|caught| caught.borrow_mut().take())
+            .unwrap_or(Panic {
+                message: String::from("Unknown panic"),
+                location: None,
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_ok() {
+        assert_eq!(catch(|| 42), Ok(42));
+    }
+
+    #[test]
+    fn test_catch_panic() {
+        let line = line!() + 1;
+        let result = catch(|| -> u8 { panic!("no signal in {}", "mjqjpqmgbljsphdz") });
+        let panic = result.unwrap_err();
+        assert_eq!(panic.message, "no signal in mjqjpqmgbljsphdz");
+        assert!(panic
+            .location
+            .unwrap()
+            .starts_with(&format!("src/guard.rs:{}:", line)));
+    }
+
+    #[test]
+    fn test_catch_unwrap() {
+        let result = catch(|| "BOOM".parse::<u32>().unwrap());
+        let panic = result.unwrap_err();
+        assert!(panic.message.contains("InvalidDigit"));
+        assert!(panic.location.unwrap().starts_with("src/guard.rs:"));
+    }
+
+    #[test]
+    fn test_catch_nested() {
+        let result = catch(|| catch(|| panic!("inner")).map_err(|panic| panic.message));
+        assert_eq!(result, Ok(Err(String::from("inner"))));
+    }
+}
@@ -0,0 +1,258 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+
+// Longest Retry-After worth waiting for, beyond that the request fails
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+// Days solved in parallel share the stamp, one request goes out at a time
+static THROTTLE: Mutex<()> = Mutex::new(());
+
+// HTTP client playing nice with adventofcode.com: it identifies itself, keeps a
+// minimum interval between requests, even across runs, and retries with backoff
+pub struct Client {
+    agent: ureq::Agent,
+    min_interval: Duration,
+    retries: u32,
+    backoff: Duration,
+    // Holds the time of the last request, in milliseconds since the epoch
+    stamp: PathBuf,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// How long to wait before the next attempt, or None when it's not worth retrying
+fn retry_delay(error: &ureq::Error, idempotent: bool, backoff: Duration) -> Option<Duration> {
+    match error {
+        ureq::Error::Status(429, response) => {
+            match response.header("Retry-After").map(str::parse::<u64>) {
+                Some(Ok(seconds)) => {
+                    Some(Duration::from_secs(seconds)).filter(|delay| *delay <= MAX_RETRY_AFTER)
+                }
+                _ => Some(backoff),
+            }
+        }
+        ureq::Error::Status(500..=599, _) if idempotent => Some(backoff),
+        ureq::Error::Transport(transport) if idempotent => match transport.kind() {
+            ureq::ErrorKind::Io | ureq::ErrorKind::ConnectionFailed => Some(backoff),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Self {
+        let user_agent = match &config.contact {
+            Some(contact) => format!("{} ({})", config.user_agent, contact),
+            None => config.user_agent.clone(),
+        };
+
+        Client {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(&user_agent)
+                .timeout(TIMEOUT)
+                .build(),
+            min_interval: config.min_interval,
+            retries: config.retries,
+            backoff: Duration::from_secs(1),
+            stamp: config.cache_dir.join("last_request"),
+        }
+    }
+
+    // A missing or unreadable stamp means no request was made recently
+    fn throttle(&self) {
+        let _guard = THROTTLE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let last = fs::read_to_string(&self.stamp)
+            .ok()
+            .and_then(|stamp| stamp.trim().parse::<u64>().ok())
+            .unwrap_or_default();
+        let elapsed = Duration::from_millis(now_millis().saturating_sub(last));
+
+        if let Some(remaining) = self.min_interval.checked_sub(elapsed) {
+            thread::sleep(remaining);
+        }
+
+        if let Some(parent) = self.stamp.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(&self.stamp, now_millis().to_string());
+    }
+
+    fn send<F>(&self, idempotent: bool, request: F) -> Result<ureq::Response, Box<ureq::Error>>
+    where
+        F: Fn(&ureq::Agent) -> Result<ureq::Response, Box<ureq::Error>>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+
+        loop {
+            self.throttle();
+            let error = match request(&self.agent) {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            attempt += 1;
+            match retry_delay(&error, idempotent, backoff) {
+                Some(delay) if attempt <= self.retries => {
+                    #[cfg(debug_assertions)]
+                    eprintln!("Request failed, retrying in {:?}: {}", delay, error);
+                    thread::sleep(delay);
+                    backoff *= 2;
+                }
+                _ => return Err(error),
+            }
+        }
+    }
+
+    pub fn get(&self, url: &str, cookie: &str) -> Result<ureq::Response, Box<ureq::Error>> {
+        self.send(true, |agent| {
+            agent
+                .get(url)
+                .set("Cookie", cookie)
+                .call()
+                .map_err(Box::new)
+        })
+    }
+
+    // Posting twice could count twice, so only a 429 is retried
+    pub fn post_form(
+        &self,
+        url: &str,
+        cookie: &str,
+        form: &[(&str, &str)],
+    ) -> Result<ureq::Response, Box<ureq::Error>> {
+        self.send(false, |agent| {
+            agent
+                .post(url)
+                .set("Cookie", cookie)
+                .send_form(form)
+                .map_err(Box::new)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{self, Reply};
+    use std::time::Instant;
+
+    fn client(name: &str, min_interval: Duration) -> Client {
+        let dir = std::env::temp_dir().join(format!("aoc-http-{}-{}", name, std::process::id()));
+        Client {
+            min_interval,
+            backoff: Duration::from_millis(10),
+            ..Client::new(&Config {
+                cache_dir: dir,
+                ..Config::default()
+            })
+        }
+    }
+
+    #[test]
+    fn test_client_user_agent() {
+        let (base_url, handle) = mock::serve(vec![Reply::new(200, "1\n")]);
+        let config = Config {
+            cache_dir: std::env::temp_dir().join(format!("aoc-http-ua-{}", std::process::id())),
+            contact: Some(String::from("elf@north.pole")),
+            min_interval: Duration::ZERO,
+            ..Config::default()
+        };
+        let client = Client::new(&config);
+        client.get(&base_url, "session=x").unwrap();
+        let requests = handle.join().unwrap();
+        let _ = fs::remove_dir_all(&config.cache_dir);
+
+        let expected = format!("User-Agent: {} (elf@north.pole)\r\n", config.user_agent);
+        assert!(requests[0].contains(&expected));
+    }
+
+    #[test]
+    fn test_client_get_retries_server_errors() {
+        let (base_url, handle) = mock::serve(vec![
+            Reply::new(502, "Bad Gateway"),
+            Reply::new(503, "Unavailable"),
+            Reply::new(200, "1\n"),
+        ]);
+        let client = client("retry", Duration::ZERO);
+        let response = client.get(&base_url, "session=x").unwrap();
+        let _ = fs::remove_file(&client.stamp);
+
+        assert_eq!(response.into_string().unwrap(), "1\n");
+        assert_eq!(handle.join().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_client_get_gives_up() {
+        let (base_url, handle) = mock::serve(vec![
+            Reply::new(500, "Oops"),
+            Reply::new(500, "Oops"),
+            Reply::new(500, "Oops"),
+            Reply::new(500, "Oops"),
+        ]);
+        let client = client("give-up", Duration::ZERO);
+        let result = client.get(&base_url, "session=x");
+        let _ = fs::remove_file(&client.stamp);
+
+        assert!(matches!(
+            result.map_err(|error| *error),
+            Err(ureq::Error::Status(500, _))
+        ));
+        assert_eq!(handle.join().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_client_post_honours_too_many_requests() {
+        let (base_url, handle) = mock::serve(vec![
+            Reply::new(429, "Slow down").header("Retry-After", "0"),
+            Reply::new(200, "Thanks"),
+        ]);
+        let client = client("post", Duration::ZERO);
+        let response = client.post_form(&base_url, "session=x", &[("level", "1")]);
+        let _ = fs::remove_file(&client.stamp);
+
+        assert_eq!(response.unwrap().into_string().unwrap(), "Thanks");
+        assert_eq!(handle.join().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_client_post_does_not_retry_server_errors() {
+        let (base_url, handle) = mock::serve(vec![Reply::new(500, "Oops")]);
+        let client = client("post-error", Duration::ZERO);
+        let result = client.post_form(&base_url, "session=x", &[("level", "1")]);
+        let _ = fs::remove_file(&client.stamp);
+
+        assert!(matches!(
+            result.map_err(|error| *error),
+            Err(ureq::Error::Status(500, _))
+        ));
+        assert_eq!(handle.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_client_throttle_persists_across_clients() {
+        let interval = Duration::from_millis(200);
+        client("throttle", interval).throttle();
+        let start = Instant::now();
+        let second = client("throttle", interval);
+        second.throttle();
+        let elapsed = start.elapsed();
+        let _ = fs::remove_file(&second.stamp);
+
+        assert!(elapsed >= Duration::from_millis(150));
+    }
+}
@@ -0,0 +1,306 @@
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::cache::{check_response, Cache, Cached};
+use crate::config::Config;
+use crate::error::Error;
+use crate::http::Client;
+use crate::schedule;
+
+pub const FIXTUREDIR: &str = "fixtures";
+
+// Anything a day’s puzzle input can be read from
+pub trait InputSource: Sync {
+    fn read(&self, day: u8) -> Result<String, Error>;
+}
+
+pub struct File(pub PathBuf);
+
+impl InputSource for File {
+    fn read(&self, day: u8) -> Result<String, Error> {
+        fs::read_to_string(&self.0).map_err(|source| Error::Input {
+            day,
+            path: Some(self.0.clone()),
+            source,
+        })
+    }
+}
+
+// Puzzle examples, kept next to the sources
+pub struct Fixture;
+
+impl InputSource for Fixture {
+    fn read(&self, day: u8) -> Result<String, Error> {
+        File(PathBuf::from(format!("{}/day_{}", FIXTUREDIR, day))).read(day)
+    }
+}
+
+pub struct Stdin;
+
+impl InputSource for Stdin {
+    fn read(&self, day: u8) -> Result<String, Error> {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|source| Error::Input {
+                day,
+                path: None,
+                source,
+            })?;
+
+        Ok(input)
+    }
+}
+
+// Fetches inputs from adventofcode.com, keeping a copy in the cache directory.
+// Puzzles still locked are refused, or waited for.
+pub struct Remote {
+    pub config: Config,
+    pub wait: bool,
+}
+
+impl InputSource for Remote {
+    fn read(&self, day: u8) -> Result<String, Error> {
+        read_input_from_remote(&self.config, day, self.wait)
+    }
+}
+
+// "-" stands for stdin, as usual
+pub fn from_options(
+    path: Option<&Path>,
+    example: bool,
+    wait: bool,
+    config: &Config,
+) -> Box<dyn InputSource> {
+    match path {
+        Some(path) if path == Path::new("-") => Box::new(Stdin),
+        Some(path) => Box::new(File(path.to_path_buf())),
+        None if example => Box::new(Fixture),
+        None => Box::new(Remote {
+            config: config.clone(),
+            wait,
+        }),
+    }
+}
+
+fn wait_for_unlock(config: &Config, day: u8, wait: bool) -> Result<(), Error> {
+    let remaining = match schedule::until_unlock(config.year, day, SystemTime::now()) {
+        Some(remaining) => remaining,
+        None => return Ok(()),
+    };
+
+    if !wait {
+        return Err(Error::Locked { day, remaining });
+    }
+
+    eprintln!(
+        "Day {} unlocks in {}, waiting",
+        day,
+        schedule::format_duration(remaining)
+    );
+    // Our clock and adventofcode.com's are rarely in sync to the second
+    thread::sleep(remaining + Duration::from_secs(1));
+
+    Ok(())
+}
+
+fn read_input_from_remote(config: &Config, day: u8, wait: bool) -> Result<String, Error> {
+    let cache = Cache::new(config);
+
+    #[cfg(debug_assertions)]
+    eprintln!("Using {} as cache", cache.input_path(day).to_string_lossy());
+
+    match cache.get(day) {
+        Ok(Cached::Hit(cached_input)) => {
+            #[cfg(debug_assertions)]
+            eprintln!("Found day {} in cache", day);
+            return Ok(cached_input);
+        }
+        Ok(Cached::Miss) => {}
+        Ok(Cached::Evicted(poison)) => {
+            eprintln!(
+                "Evicted day {} from cache, fetching it again: {}",
+                day, poison
+            )
+        }
+        Err(_error) => {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "{}",
+                Error::Cache {
+                    day,
+                    source: _error
+                }
+                .report()
+            );
+        }
+    };
+
+    wait_for_unlock(config, day, wait)?;
+
+    let token = config.token().map_err(|source| Error::Config { source })?;
+    let path = format!("{}/{}/day/{}/input", config.base_url, config.year, day);
+    let cookie = format!("session={}", token);
+    let (status, body) = Client::new(config)
+        .get(&path, &cookie)
+        .and_then(|response| {
+            let status = response.status();
+            let body = response
+                .into_string()
+                .map_err(|error| Box::new(ureq::Error::from(error)))?;
+            Ok((status, body))
+        })
+        .map_err(|source| Error::Fetch { day, source })?;
+
+    check_response(status, &body).map_err(|source| Error::Rejected { day, source })?;
+
+    if let Err(_error) = cache.put(day, &body) {
+        #[cfg(debug_assertions)]
+        eprintln!(
+            "{}",
+            Error::Cache {
+                day,
+                source: _error
+            }
+            .report()
+        );
+    };
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::Poison;
+    use crate::config::TokenSource;
+    use crate::mock::{self, Reply};
+
+    #[test]
+    fn test_file_read_ok() {
+        let result = File(PathBuf::from("fixtures/day_12")).read(12).unwrap();
+        assert_eq!(result, "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi\n");
+    }
+
+    #[test]
+    fn test_fixture_read_ok() {
+        let result = Fixture.read(1).unwrap();
+        assert!(result.starts_with("1000\n2000\n3000\n"));
+    }
+
+    #[test]
+    fn test_remote_read_ok() {
+        let (base_url, handle) = mock::serve(vec![Reply::new(200, "1\n2\n")]);
+        let dir = std::env::temp_dir().join(format!("aoc-remote-{}", std::process::id()));
+        let token = dir.join("token");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&token, "t0k3n").unwrap();
+        let config = Config {
+            base_url,
+            year: 2021,
+            cache_dir: dir.clone(),
+            token: TokenSource::File(token),
+            min_interval: Duration::ZERO,
+            ..Config::default()
+        };
+
+        let remote = Remote {
+            config,
+            wait: false,
+        };
+        let result = remote.read(3);
+        let cached = remote.read(3);
+        let requests = handle.join().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(result.unwrap(), "1\n2\n");
+        assert_eq!(cached.unwrap(), "1\n2\n");
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2021/day/3/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=t0k3n\r\n"));
+    }
+
+    #[test]
+    fn test_remote_read_error_page_err() {
+        let (base_url, handle) = mock::serve(vec![Reply::new(
+            200,
+            "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+        )]);
+        let dir = std::env::temp_dir().join(format!("aoc-poison-{}", std::process::id()));
+        // Set by this test only, so the cookie doesn't depend on the environment
+        std::env::set_var("AOC_TOKEN_POISON_TEST", "t0k3n");
+        let config = Config {
+            base_url,
+            year: 2022,
+            cache_dir: dir.clone(),
+            token: TokenSource::Env(String::from("AOC_TOKEN_POISON_TEST")),
+            min_interval: Duration::ZERO,
+            ..Config::default()
+        };
+
+        let result = Remote {
+            config,
+            wait: false,
+        }
+        .read(4);
+        let requests = handle.join().unwrap();
+        let cached = dir.join("2022").join("day_4").exists();
+        let _ = fs::remove_dir_all(&dir);
+
+        assert!(matches!(
+            result,
+            Err(Error::Rejected {
+                day: 4,
+                source: Poison::ErrorPage
+            })
+        ));
+        assert!(!cached);
+        assert!(requests[0].contains("Cookie: session=t0k3n\r\n"));
+    }
+
+    #[test]
+    fn test_remote_read_missing_token_err() {
+        let config = Config {
+            cache_dir: PathBuf::from("/nonexistent"),
+            token: TokenSource::Env(String::from("AOC_TOKEN_THAT_IS_NOT_SET")),
+            ..Config::default()
+        };
+        let result = Remote {
+            config,
+            wait: false,
+        }
+        .read(3);
+        assert!(matches!(result, Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_remote_read_locked_err() {
+        let config = Config {
+            year: 2099,
+            cache_dir: PathBuf::from("/nonexistent"),
+            ..Config::default()
+        };
+        let result = Remote {
+            config,
+            wait: false,
+        }
+        .read(25);
+        assert!(matches!(result, Err(Error::Locked { day: 25, .. })));
+    }
+
+    #[test]
+    fn test_file_read_err() {
+        let result = File(PathBuf::from("fixtures/day_0")).read(12);
+        assert!(matches!(
+            result,
+            Err(Error::Input {
+                day: 12,
+                path: Some(_),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, prelude::*};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::Config;
+use crate::error::ParseError;
+use crate::solution::Part;
+use crate::submit::Outcome;
+
+// What adventofcode.com told us about the answers to one part
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Entry {
+    verified: Option<String>,
+    wrong: Vec<String>,
+    // Lowest answer known to be too high and highest known to be too low
+    too_high: Option<i128>,
+    too_low: Option<i128>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Mark {
+    Regression,
+    Unknown,
+    Verified,
+}
+
+impl std::fmt::Display for Mark {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Regression => write!(f, "✗"),
+            Self::Unknown => write!(f, "?"),
+            Self::Verified => write!(f, "✓"),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Rejection {
+    AboveBound(i128),
+    BelowBound(i128),
+    DiffersFromVerified(String),
+    KnownWrong,
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AboveBound(bound) => write!(f, "{} was already too high", bound),
+            Self::BelowBound(bound) => write!(f, "{} was already too low", bound),
+            Self::DiffersFromVerified(answer) => write!(f, "{} is the verified answer", answer),
+            Self::KnownWrong => write!(f, "answer was already rejected"),
+        }
+    }
+}
+
+impl Entry {
+    fn update(&mut self, answer: &str, outcome: Outcome) {
+        let number = answer.parse::<i128>().ok();
+
+        match outcome {
+            Outcome::Correct => self.verified = Some(answer.to_string()),
+            Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong => {
+                if !self.wrong.iter().any(|wrong| wrong == answer) {
+                    self.wrong.push(answer.to_string());
+                }
+            }
+            Outcome::AlreadySolved | Outcome::RateLimited(_) => {}
+        }
+
+        match (outcome, number) {
+            (Outcome::TooHigh, Some(number)) => {
+                self.too_high = Some(self.too_high.map_or(number, |bound| bound.min(number)))
+            }
+            (Outcome::TooLow, Some(number)) => {
+                self.too_low = Some(self.too_low.map_or(number, |bound| bound.max(number)))
+            }
+            _ => {}
+        }
+    }
+}
+
+// Every submission ever made, replayed from a tab separated log:
+// time, day, part, outcome and answer
+#[derive(Debug, Default)]
+pub struct Ledger {
+    entries: HashMap<(u8, Part), Entry>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum LedgerLineParseError {
+    InvalidDay(String),
+    InvalidOutcome(String),
+    InvalidPart(String),
+    MissingField,
+}
+
+impl std::fmt::Display for LedgerLineParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidDay(s) => write!(f, "Invalid day {:?}", s),
+            Self::InvalidOutcome(s) => write!(f, "Invalid outcome {:?}", s),
+            Self::InvalidPart(s) => write!(f, "Invalid part {:?}", s),
+            Self::MissingField => write!(f, "Expected time, day, part, outcome and answer"),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Result<(u8, Part, Outcome, &str), LedgerLineParseError> {
+    match line.splitn(5, '\t').collect::<Vec<&str>>()[..] {
+        [_, day, part, outcome, answer] => Ok((
+            day.parse()
+                .map_err(|_| LedgerLineParseError::InvalidDay(day.to_string()))?,
+            part.parse()
+                .map_err(|_| LedgerLineParseError::InvalidPart(part.to_string()))?,
+            outcome
+                .parse()
+                .map_err(|_| LedgerLineParseError::InvalidOutcome(outcome.to_string()))?,
+            answer,
+        )),
+        _ => Err(LedgerLineParseError::MissingField),
+    }
+}
+
+impl FromStr for Ledger {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ledger = Ledger::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let (day, part, outcome, answer) = parse_line(line)
+                .map_err(|error| ParseError::new(error).at_line(index + 1, line))?;
+            ledger.update(day, part, answer, outcome);
+        }
+
+        Ok(ledger)
+    }
+}
+
+#[derive(Debug)]
+pub enum LedgerError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(_) => write!(f, "Unable to access the ledger"),
+            Self::Parse(_) => write!(f, "Unable to parse the ledger"),
+        }
+    }
+}
+
+impl std::error::Error for LedgerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Parse(error) => Some(error),
+        }
+    }
+}
+
+pub fn ledger_path(config: &Config) -> PathBuf {
+    config.year_dir().join("submissions")
+}
+
+impl Ledger {
+    // A missing ledger is just an empty one
+    pub fn load(path: &Path) -> Result<Self, LedgerError> {
+        match fs::read_to_string(path) {
+            Ok(content) => content.parse().map_err(LedgerError::Parse),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Ledger::default()),
+            Err(error) => Err(LedgerError::Io(error)),
+        }
+    }
+
+    fn update(&mut self, day: u8, part: Part, answer: &str, outcome: Outcome) {
+        self.entries
+            .entry((day, part))
+            .or_default()
+            .update(answer.trim(), outcome);
+    }
+
+    // Updates the ledger and appends the submission to its log
+    pub fn record(
+        &mut self,
+        path: &Path,
+        day: u8,
+        part: Part,
+        answer: &str,
+        outcome: Outcome,
+    ) -> io::Result<()> {
+        self.update(day, part, answer, outcome);
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}",
+            time,
+            day,
+            part,
+            outcome.code(),
+            answer.trim()
+        )
+    }
+
+    pub fn mark(&self, day: u8, part: Part, answer: &str) -> Mark {
+        let answer = answer.trim();
+        match self.entries.get(&(day, part)) {
+            Some(Entry {
+                verified: Some(verified),
+                ..
+            }) if verified == answer => Mark::Verified,
+            Some(Entry {
+                verified: Some(_), ..
+            }) => Mark::Regression,
+            Some(entry) if entry.wrong.iter().any(|wrong| wrong == answer) => Mark::Regression,
+            _ => Mark::Unknown,
+        }
+    }
+
+    // Guesses that can only be wrong are not worth a submission, nor the wait after it
+    pub fn check_guess(&self, day: u8, part: Part, answer: &str) -> Result<(), Rejection> {
+        let answer = answer.trim();
+        let entry = match self.entries.get(&(day, part)) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+
+        if let Some(verified) = &entry.verified {
+            return match verified == answer {
+                true => Ok(()),
+                false => Err(Rejection::DiffersFromVerified(verified.clone())),
+            };
+        }
+
+        if entry.wrong.iter().any(|wrong| wrong == answer) {
+            return Err(Rejection::KnownWrong);
+        }
+
+        if let Ok(number) = answer.parse::<i128>() {
+            if let Some(bound) = entry.too_high.filter(|bound| number >= *bound) {
+                return Err(Rejection::AboveBound(bound));
+            }
+            if let Some(bound) = entry.too_low.filter(|bound| number <= *bound) {
+                return Err(Rejection::BelowBound(bound));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "1670000000\t1\t1\ttoo-high\t30000
+1670000060\t1\t1\ttoo-low\t20000
+1670000120\t1\t1\trate-limited\t25000
+1670000180\t1\t1\twrong\t24500
+1670000240\t1\t1\tcorrect\t24000
+1670000300\t1\t2\ttoo-low\t40000
+";
+
+    #[test]
+    fn test_ledger_from_str_err() {
+        let result = "1670000000\t1\t3\tcorrect\t1".parse::<Ledger>();
+        assert_eq!(
+            result.err(),
+            Some(
+                ParseError::new(LedgerLineParseError::InvalidPart(String::from("3")))
+                    .at_line(1, "1670000000\t1\t3\tcorrect\t1")
+            )
+        );
+    }
+
+    #[test]
+    fn test_ledger_mark() {
+        let ledger = LOG.parse::<Ledger>().unwrap();
+        assert_eq!(ledger.mark(1, Part::One, "24000\n"), Mark::Verified);
+        assert_eq!(ledger.mark(1, Part::One, "24001"), Mark::Regression);
+        assert_eq!(ledger.mark(1, Part::Two, "40000"), Mark::Regression);
+        assert_eq!(ledger.mark(1, Part::Two, "45000"), Mark::Unknown);
+        assert_eq!(ledger.mark(2, Part::One, "15"), Mark::Unknown);
+    }
+
+    #[test]
+    fn test_ledger_check_guess() {
+        let ledger = LOG.parse::<Ledger>().unwrap();
+        assert_eq!(ledger.check_guess(1, Part::One, "24000"), Ok(()));
+        assert_eq!(
+            ledger.check_guess(1, Part::One, "25000"),
+            Err(Rejection::DiffersFromVerified(String::from("24000")))
+        );
+        assert_eq!(ledger.check_guess(1, Part::Two, "45000"), Ok(()));
+        assert_eq!(
+            ledger.check_guess(1, Part::Two, "40000"),
+            Err(Rejection::KnownWrong)
+        );
+        assert_eq!(
+            ledger.check_guess(1, Part::Two, "39999"),
+            Err(Rejection::BelowBound(40000))
+        );
+    }
+
+    #[test]
+    fn test_ledger_check_guess_bounds() {
+        let log = "0\t5\t2\ttoo-high\t100\n0\t5\t2\ttoo-high\t90\n0\t5\t2\ttoo-low\t10\n";
+        let ledger = log.parse::<Ledger>().unwrap();
+        assert_eq!(
+            ledger.check_guess(5, Part::Two, "95"),
+            Err(Rejection::AboveBound(90))
+        );
+        assert_eq!(
+            ledger.check_guess(5, Part::Two, "7"),
+            Err(Rejection::BelowBound(10))
+        );
+        assert_eq!(ledger.check_guess(5, Part::Two, "50"), Ok(()));
+    }
+
+    #[test]
+    fn test_ledger_record_and_load() {
+        let path = std::env::temp_dir().join(format!("aoc-ledger-{}", std::process::id()));
+        let mut ledger = Ledger::load(&path).unwrap();
+        ledger
+            .record(&path, 3, Part::One, "157\n", Outcome::Correct)
+            .unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        let loaded = Ledger::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(content.ends_with("\t3\t1\tcorrect\t157\n"));
+        assert_eq!(ledger.mark(3, Part::One, "157"), Mark::Verified);
+        assert_eq!(loaded.mark(3, Part::One, "157"), Mark::Verified);
+    }
+}
@@ -1,8 +1,13 @@
 use std::env;
-use std::fs;
-use std::io::{self, prelude::*};
+use std::path::Path;
+use std::process::ExitCode;
 use std::time::Instant;
 
+mod bench;
+mod cache;
+mod cancel;
+mod cli;
+mod config;
 mod day_1;
 mod day_10;
 mod day_11;
@@ -15,238 +20,451 @@ mod day_6;
 mod day_7;
 mod day_8;
 mod day_9;
+mod error;
+mod example;
+mod grid;
+mod guard;
+mod http;
+mod input;
+mod ledger;
+#[cfg(test)]
+mod mock;
+mod ocr;
+mod pool;
+mod puzzle;
+mod render;
+mod report;
+mod scaffold;
+mod schedule;
+mod search;
+mod solution;
+mod submit;
+
+// Reading the input doesn't count towards the time limits
+fn solve(
+    day: &'static solution::Day,
+    source: &dyn input::InputSource,
+    parts: &[solution::Part],
+    limits: cancel::Limits,
+) -> Result<Vec<(solution::Part, String)>, error::Error> {
+    let input = source.read(day.number)?;
+    cancel::solve_within(day, input, parts, limits)
+}
 
-#[derive(Debug)]
-struct SolutionError<T>(T);
-
-impl std::error::Error for SolutionError<day_1::SolutionError> {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(&self.0)
-    }
+fn load_config(settings: &[(String, String)]) -> Option<config::Config> {
+    config::Config::load(settings)
+        .map_err(|source| eprintln!("Error: {}", error::Error::Config { source }.report()))
+        .ok()
 }
 
-impl std::fmt::Display for SolutionError<day_1::SolutionError> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Failed to solve day 1, part 1")
+fn run(options: cli::RunOptions) -> ExitCode {
+    let config = match load_config(&options.settings) {
+        Some(config) => config,
+        None => return ExitCode::FAILURE,
+    };
+    let main_start = Instant::now();
+    let mut failed_days = Vec::new();
+    let mut reports = Vec::new();
+    let source = input::from_options(
+        options.input.as_deref(),
+        options.example,
+        options.wait,
+        &config,
+    );
+    let parts: Vec<solution::Part> = solution::Part::ALL
+        .into_iter()
+        .filter(|part| options.includes(*part))
+        .collect();
+    // Answers are only known for our own input
+    let ledger = match (&options.input, options.example) {
+        (None, false) => ledger::Ledger::load(&ledger::ledger_path(&config))
+            .map_err(|source| eprintln!("{}", error::Error::Ledger { source }.report()))
+            .ok(),
+        _ => None,
+    };
+    let days: Vec<&'static solution::Day> = options
+        .days
+        .iter()
+        .filter_map(|day| solution::find(*day))
+        .collect();
+    // Each day is timed by the worker solving it, waiting for a free worker doesn't count
+    let work = |day: &&'static solution::Day| {
+        let day_start = Instant::now();
+        let result = solve(day, source.as_ref(), &parts, options.limits).and_then(|answers| {
+            let expected = match options.example {
+                true => example::read_expected(day.number)?,
+                false => None,
+            };
+            Ok((answers, expected))
+        });
+        let elapsed = day_start.elapsed();
+        let result = result.map(|(answers, expected)| {
+            answers
+                .into_iter()
+                .map(|(part, answer)| {
+                    let check = match (&expected, &ledger) {
+                        (Some(expected), _) => expected.get(part).map(|expected| {
+                            report::Check::Example(example::Verdict::check(expected, &answer))
+                        }),
+                        (None, Some(ledger)) => Some(report::Check::Ledger(
+                            ledger.mark(day.number, part, &answer),
+                        )),
+                        (None, None) => None,
+                    };
+                    report::PartReport {
+                        part,
+                        answer,
+                        check,
+                    }
+                })
+                .collect()
+        });
+        report::DayReport {
+            day: day.number,
+            elapsed,
+            result,
+        }
+    };
+    pool::for_each_ordered(&days, options.jobs, work, |report| {
+        if !report.passed() {
+            failed_days.push((report.day, report.status()));
+        }
+        match options.format {
+            report::Format::Json => reports.push(report.json()),
+            report::Format::Ndjson => println!("{}", report.json()),
+            report::Format::Text => report.print_text(),
+        }
+    });
+
+    match options.format {
+        report::Format::Json => {
+            let failed: Vec<String> = failed_days.iter().map(|(day, _)| day.to_string()).collect();
+            println!(
+                "{{\"days\":[{}],\"elapsed_ns\":{},\"failed_days\":[{}]}}",
+                reports.join(","),
+                main_start.elapsed().as_nanos(),
+                failed.join(",")
+            );
+        }
+        report::Format::Ndjson => {}
+        report::Format::Text => println!("All done in {:?}", main_start.elapsed()),
     }
-}
 
-impl From<day_1::SolutionError> for SolutionError<day_1::SolutionError> {
-    fn from(error: day_1::SolutionError) -> Self {
-        SolutionError(error)
+    // Every day ran, whatever happened to the others
+    if failed_days.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        let failed_days: Vec<String> = failed_days
+            .iter()
+            .map(|(day, status)| format!("{} ({})", day, status))
+            .collect();
+        eprintln!("Failed days: {}", failed_days.join(", "));
+        ExitCode::FAILURE
     }
 }
 
-const CACHEDIR: &'static str = ".cache";
+fn bench(options: cli::BenchOptions) -> ExitCode {
+    let config = match load_config(&options.run.settings) {
+        Some(config) => config,
+        None => return ExitCode::FAILURE,
+    };
+    let baseline = match &options.baseline {
+        Some(path) => match bench::Baseline::load(path) {
+            Ok(baseline) => Some(baseline),
+            Err(source) => {
+                eprintln!("Error: {}", error::Error::Baseline { source }.report());
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    let source = input::from_options(
+        options.run.input.as_deref(),
+        options.run.example,
+        options.run.wait,
+        &config,
+    );
+    let parts: Vec<solution::Part> = solution::Part::ALL
+        .into_iter()
+        .filter(|part| options.run.includes(*part))
+        .collect();
+    let mut medians = bench::Baseline::default();
+    let mut failed_days = Vec::new();
+    let mut regressions = Vec::new();
+
+    for day in options
+        .run
+        .days
+        .iter()
+        .filter_map(|day| solution::find(*day))
+    {
+        println!("Day {}, {} runs", day.number, options.runs);
+        let result = source
+            .read(day.number)
+            .and_then(|input| bench::bench_day(day, &input, &parts, options.warmup, options.runs));
+        match result {
+            Ok(stages) => {
+                for (stage, stats) in stages {
+                    let change = baseline
+                        .as_ref()
+                        .and_then(|baseline| baseline.change(day.number, stage, stats.median));
+                    match change {
+                        Some(change) if change > options.threshold as f64 => {
+                            println!("\t{:<7} {}  {:+.1}% (regression)", stage, stats, change);
+                            regressions.push(format!("day {} {}", day.number, stage));
+                        }
+                        Some(change) => println!("\t{:<7} {}  {:+.1}%", stage, stats, change),
+                        None => println!("\t{:<7} {}", stage, stats),
+                    }
+                    medians.insert(day.number, stage, stats.median);
+                }
+            }
+            Err(error) => {
+                eprintln!("\tError: {}", report::indent_answer(&error.report()));
+                failed_days.push(day.number.to_string());
+            }
+        }
+        println!();
+    }
 
-fn read_input_from_file(file_path: std::path::PathBuf) -> io::Result<Vec<String>> {
-    let file = fs::File::open(file_path)?;
-    let reader = io::BufReader::new(file);
-    let mut result = Vec::new();
-    for line in reader.lines() {
-        if let Err(error) = line {
-            return Err(error);
+    if let Some(path) = &options.save {
+        if let Err(source) = medians.save(path) {
+            eprintln!("Error: {}", error::Error::Baseline { source }.report());
+            return ExitCode::FAILURE;
         }
+    }
 
-        result.push(line.unwrap())
+    if !failed_days.is_empty() {
+        eprintln!("Failed days: {}", failed_days.join(", "));
+    }
+    if !regressions.is_empty() {
+        eprintln!("Regressions: {}", regressions.join(", "));
     }
 
-    return Ok(result);
+    match failed_days.is_empty() && regressions.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
 }
 
-fn write_input_to_file(file_path: std::path::PathBuf, input: &String) -> Result<(), io::Error> {
-    fs::create_dir_all(CACHEDIR)?;
-
-    let file = fs::File::create(file_path)?;
-    let mut file = io::LineWriter::new(file);
-
-    file.write_all(input.as_bytes())?;
-    file.flush()?;
+fn submit(options: cli::SubmitOptions) -> ExitCode {
+    let config = match load_config(&options.settings) {
+        Some(config) => config,
+        None => return ExitCode::FAILURE,
+    };
+    let source = input::from_options(options.input.as_deref(), false, false, &config);
+    let day = options.day;
+    let part = options.part;
+    let result = config
+        .token()
+        .map_err(|source| error::Error::Config { source })
+        .and_then(|token| {
+            let ledger = ledger::Ledger::load(&ledger::ledger_path(&config))
+                .map_err(|source| error::Error::Ledger { source })?;
+            // solution::find can't fail, the day was checked against the registry
+            let answers = solve(
+                solution::find(day).unwrap(),
+                source.as_ref(),
+                &[part],
+                cancel::Limits::default(),
+            )?;
+            let answer = answers
+                .into_iter()
+                .map(|(_, answer)| answer)
+                .collect::<String>();
+            ledger
+                .check_guess(day, part, &answer)
+                .map_err(submit::SubmitError::KnownWrong)
+                .map_err(|source| error::Error::Submit { day, part, source })?;
+            let submitter = submit::Submitter {
+                client: http::Client::new(&config),
+                base_url: config.base_url.clone(),
+                year: config.year,
+                token,
+            };
+            let outcome = submitter
+                .submit(day, part, &answer)
+                .map_err(|source| error::Error::Submit { day, part, source })?;
+            Ok((answer, outcome, ledger))
+        });
+
+    match result {
+        Ok((answer, outcome, mut ledger)) => {
+            println!(
+                "Day {}, part {}: {}",
+                day,
+                part,
+                report::indent_answer(&answer)
+            );
+            println!("\t{}", outcome);
+
+            let log = ledger::ledger_path(&config);
+            if let Err(error) = ledger.record(&log, day, part, &answer, outcome) {
+                eprintln!(
+                    "Unable to record the outcome in {}: {}",
+                    log.display(),
+                    error
+                );
+            }
 
-    Ok(())
+            match outcome {
+                submit::Outcome::Correct | submit::Outcome::AlreadySolved => ExitCode::SUCCESS,
+                _ => ExitCode::FAILURE,
+            }
+        }
+        Err(error) => {
+            eprintln!("Error: {}", report::indent_answer(&error.report()));
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn read_input_from_remote(day: String) -> Result<Vec<String>, ureq::Error> {
-    let file_path = std::path::PathBuf::from(format!("{}/day_{}", CACHEDIR, day));
-
-    #[cfg(debug_assertions)]
-    eprintln!("Using {} as cache", file_path.to_string_lossy());
-
-    if let Ok(cached_input) = read_input_from_file(file_path.clone()) {
-        #[cfg(debug_assertions)]
-        eprintln!("Found day {} in cache", day);
-        return Ok(cached_input);
+fn example(options: cli::ExampleOptions) -> ExitCode {
+    let day = options.day;
+    let config = match load_config(&options.settings) {
+        Some(config) => config,
+        None => return ExitCode::FAILURE,
     };
-
-    let token = env::var("AOC_TOKEN").expect("no $AOC_TOKEN defined");
-    let path = format!("https://adventofcode.com/2022/day/{}/input", day);
-    let cookie = format!("session={}", token);
-    let body = ureq::get(&path)
-        .set("Cookie", &cookie)
-        .call()?
-        .into_string()?;
-
-    if let Err(_error) = write_input_to_file(file_path, &body) {
-        #[cfg(debug_assertions)]
-        eprintln!("Could not save day {} to cache", day);
-        #[cfg(debug_assertions)]
-        eprintln!("{}", _error);
+    let html = match puzzle::fetch(&config, day) {
+        Ok(html) => html,
+        Err(error) => {
+            eprintln!("Error: {}", report::indent_answer(&error.report()));
+            return ExitCode::FAILURE;
+        }
     };
 
-    let mut result = Vec::new();
-    for line in body.lines() {
-        result.push(line.to_string())
+    if options.show {
+        println!("{}", puzzle::text(&html));
     }
 
-    Ok(result)
+    let written = puzzle::Example::from_html(&html)
+        .ok_or(puzzle::PuzzleError::NoExample)
+        .and_then(|example| {
+            scaffold::write_example(Path::new("."), day, &example, options.force)
+                .map(|paths| (example, paths))
+                .map_err(puzzle::PuzzleError::Fixture)
+        });
+
+    match written {
+        Ok((example, paths)) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
+            }
+            print!("{}", example.expected());
+            ExitCode::SUCCESS
+        }
+        Err(source) => {
+            let error = error::Error::Puzzle { day, source };
+            eprintln!("Error: {}", report::indent_answer(&error.report()));
+            ExitCode::FAILURE
+        }
+    }
 }
 
-fn solve(day: i8) -> Result<(String, String), Box<dyn std::error::Error>> {
-    let input: io::Result<Vec<String>> = match read_input_from_remote(day.to_string()).ok() {
-        Some(input) => Ok(input),
-        None => panic!("could not fetch"),
-    };
-
-    let raw_data = input.unwrap();
-    return match day {
-        1 => {
-            let input = raw_data.join("\n");
-            let part_1 = day_1::solve_first(&input)?;
-            let part_2 = day_1::solve_second(&input)?;
-            Ok((part_1, part_2))
-        }
-        2 => Ok(day_2::solve(raw_data)),
-        3 => Ok(day_3::solve(raw_data)),
-        4 => Ok(day_4::solve(raw_data)),
-        5 => Ok(day_5::solve(raw_data.join("\n"))),
-        6 => Ok(day_6::solve(raw_data.join("\n"))),
-        7 => Ok(day_7::solve(raw_data.join("\n"))),
-        8 => Ok(day_8::solve(raw_data.join("\n"))),
-        9 => Ok(day_9::solve(raw_data.join("\n"))),
-        10 => {
-            let (answer_1, answer_2) = day_10::solve(raw_data.join("\n"));
-
-            // Add some tabulation for better output
-            let answer_2: String = answer_2
-                .lines()
-                .map(|line| ["\t\t", line, "\n"].concat())
-                .collect();
-
-            // Remove tabulation on first line
-            let answer_2 = answer_2.trim_start().to_string();
-
-            Ok((answer_1, answer_2))
-        }
-        11 => {
-            let input = raw_data.join("\n");
-            let part_1 = day_11::solve_first(&input)?;
-            let part_2 = day_11::solve_second(&input)?;
-            Ok((part_1, part_2))
-        }
-        12 => {
-            let input = raw_data.join("\n");
-            let part_1 = day_12::solve_first(&input)?;
-            let part_2 = day_12::solve_second(&input)?;
-            Ok((part_1, part_2))
-        }
-        _ => panic! {"bad day"},
+fn new(options: cli::NewOptions) -> ExitCode {
+    let day = options.day;
+    let example = match options.fetch {
+        true => {
+            let config = match load_config(&options.settings) {
+                Some(config) => config,
+                None => return ExitCode::FAILURE,
+            };
+            match puzzle::fetch(&config, day) {
+                Ok(html) => {
+                    let example = puzzle::Example::from_html(&html);
+                    if example.is_none() {
+                        eprintln!(
+                            "No example found for day {}, its fixture is left empty",
+                            day
+                        );
+                    }
+                    example
+                }
+                Err(error) => {
+                    eprintln!("Error: {}", report::indent_answer(&error.report()));
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        false => None,
     };
-}
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let main_start = Instant::now();
-    for day in 1..=12 {
-        let day_start = Instant::now();
-        let result = solve(day);
-        let elapsed = day_start.elapsed();
-        println!("Day {}, done in {:?}", day, elapsed);
-        match result {
-            Ok((answer_1, answer_2)) => {
-                println!("\tPart 1: {}", answer_1);
-                println!("\tPart 2: {}", answer_2);
+    match scaffold::create(Path::new("."), day, example.as_ref()) {
+        Ok(paths) => {
+            for path in paths {
+                println!("Wrote {}", path.display());
             }
-            Err(error) => {
-                eprintln!("\tError: {}", error);
-            }
-        };
-        println!("");
+            println!(
+                "Day {} is ready to be solved, its tests are ignored until then",
+                day
+            );
+            ExitCode::SUCCESS
+        }
+        Err(source) => {
+            let error = error::Error::Scaffold { day, source };
+            eprintln!("Error: {}", report::indent_answer(&error.report()));
+            ExitCode::FAILURE
+        }
     }
-    println!("All done in {:?}", main_start.elapsed());
-    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    fn read_fixture_from_file(day: i32) -> String {
-        let file_path = std::path::PathBuf::from(format!("{}/day_{}", "fixtures", day));
-        read_input_from_file(file_path).unwrap().join("\n")
-    }
-
-    #[test]
-    fn test_solve_day_1_part_1_ok() {
-        let input = read_fixture_from_file(1);
-        let result = day_1::solve_first(&input).unwrap();
-        assert_eq!(result, String::from("24000"));
-    }
-
-    #[test]
-    fn test_solve_day_1_part_1_err() {
-        let input = String::from("BOOM");
-        let result = day_1::solve_first(&input);
-        assert_eq!(
-            result,
-            Err(day_1::SolutionError::TroupeParseError(
-                day_1::TroupeParseError
-            ))
-        );
-    }
-
-    #[test]
-    fn test_solve_day_1_part_2_ok() {
-        let input = read_fixture_from_file(1);
-        let result = day_1::solve_second(&input).unwrap();
-        assert_eq!(result, String::from("45000"));
-    }
-
-    #[test]
-    fn test_solve_day_1_part_2_err() {
-        let input = String::from("BOOM");
-        let result = day_1::solve_second(&input);
-        assert_eq!(
-            result,
-            Err(day_1::SolutionError::TroupeParseError(
-                day_1::TroupeParseError
-            ))
-        );
-    }
-
-    #[test]
-    fn test_solve_day_11_part_1_ok() {
-        let input = read_fixture_from_file(11);
-        let result = day_11::solve_first(&input).unwrap();
-        assert_eq!(result, String::from("10605"));
-    }
+fn render(options: cli::RenderOptions) -> ExitCode {
+    let config = match load_config(&options.settings) {
+        Some(config) => config,
+        None => return ExitCode::FAILURE,
+    };
+    let source = input::from_options(options.input.as_deref(), options.example, false, &config);
+    // solution::find can't fail, the day was checked against the registry
+    let day = solution::find(options.day).unwrap();
+    let rendered = source
+        .read(day.number)
+        .and_then(|input| day.parse(&input))
+        .map(|parsed| parsed.render());
+
+    let render = match rendered {
+        Ok(Some(render)) => render,
+        Ok(None) => {
+            eprintln!("Day {} has nothing to render", day.number);
+            return ExitCode::FAILURE;
+        }
+        Err(error) => {
+            eprintln!("Error: {}", report::indent_answer(&error.report()));
+            return ExitCode::FAILURE;
+        }
+    };
 
-    #[test]
-    fn test_solve_day_11_part_2_ok() {
-        let input = read_fixture_from_file(11);
-        let result = day_11::solve_second(&input).unwrap();
-        assert_eq!(result, String::from("2713310158"));
+    print!("{}", render.text);
+    match (render.image, options.image) {
+        (Some(image), Some(path)) => match image.save(&path) {
+            Ok(()) => println!("Saved the image to {}", path.display()),
+            Err(source) => {
+                let error = error::Error::Image { path, source };
+                eprintln!("Error: {}", report::indent_answer(&error.report()));
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, Some(_)) => eprintln!("Day {} only renders as text", day.number),
+        (_, None) => {}
     }
 
-    #[test]
-    fn test_solve_day_12_part_1_ok() {
-        let input = read_fixture_from_file(12);
-        let result = day_12::solve_first(&input).unwrap();
-        assert_eq!(result, String::from("31"));
-    }
+    ExitCode::SUCCESS
+}
 
-    #[test]
-    fn test_solve_day_12_part_2_ok() {
-        let input = read_fixture_from_file(12);
-        let result = day_12::solve_second(&input).unwrap();
-        assert_eq!(result, String::from("29"));
+fn main() -> ExitCode {
+    match cli::Command::from_args(env::args().skip(1), &solution::days()) {
+        Ok(cli::Command::Bench(options)) => bench(options),
+        Ok(cli::Command::Example(options)) => example(options),
+        Ok(cli::Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Ok(cli::Command::New(options)) => new(options),
+        Ok(cli::Command::Render(options)) => render(options),
+        Ok(cli::Command::Run(options)) => run(options),
+        Ok(cli::Command::Submit(options)) => submit(options),
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, cli::USAGE);
+            ExitCode::from(2)
+        }
     }
 }
@@ -0,0 +1,204 @@
+use std::ops::Range;
+
+// Reads the capital letters drawn by some puzzles, with '#' for lit pixels
+struct Font {
+    width: usize,
+    height: usize,
+    // Blank columns between two letters, that a few letters spill into
+    gap: usize,
+    glyphs: &'static [(char, &'static [&'static str])],
+}
+
+// The usual font, 4×6
+const SMALL: Font = Font {
+    width: 4,
+    height: 6,
+    gap: 1,
+    glyphs: &[
+        ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+        ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+        ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+        ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+        ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+        ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+        ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+        ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+        ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+        ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+        ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+        ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+        ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+        ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+        ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+        ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+    ],
+};
+
+// The larger font of some other years, 6×10
+#[rustfmt::skip]
+const LARGE: Font = Font {
+    width: 6,
+    height: 10,
+    gap: 2,
+    glyphs: &[
+        ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+        ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+        ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+        ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+        ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+        ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+        ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+        ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+        ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+        ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+        ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+        ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+        ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+    ],
+};
+
+impl Font {
+    // The letter drawn in the columns, whatever they hold past it being blank
+    fn glyph(&self, rows: &[Vec<bool>], columns: Range<usize>) -> Option<char> {
+        self.glyphs
+            .iter()
+            .find(|(_, glyph)| {
+                glyph.iter().zip(rows).all(|(glyph_row, row)| {
+                    let cell = &row[columns.clone()];
+                    glyph_row.len() <= cell.len()
+                        && glyph_row
+                            .chars()
+                            .map(|pixel| pixel == '#')
+                            .chain(std::iter::repeat(false))
+                            .zip(cell)
+                            .all(|(pixel, lit)| pixel == *lit)
+                })
+            })
+            .map(|(letter, _)| *letter)
+    }
+
+    fn read(&self, rows: &[Vec<bool>]) -> Option<String> {
+        let width = rows.first()?.len();
+        let stride = self.width + self.gap;
+        // The last letter doesn't need its gap
+        let count = (width + self.gap) / stride;
+        let blank = |column: usize| rows.iter().all(|row| !row[column]);
+
+        if count == 0 || (count * stride..width).any(|column| !blank(column)) {
+            return None;
+        }
+
+        (0..count)
+            .map(|index| {
+                let column = index * stride;
+                self.glyph(rows, column..(column + stride).min(width))
+            })
+            .collect()
+    }
+}
+
+// None unless every letter is known, trailing whitespace aside
+pub fn recognize(image: &str) -> Option<String> {
+    let rows: Vec<Vec<bool>> = image
+        .trim_end()
+        .lines()
+        .map(|line| line.chars().map(|pixel| pixel == '#').collect())
+        .collect();
+
+    let width = rows.first()?.len();
+    if rows.iter().any(|row| row.len() != width) {
+        return None;
+    }
+
+    [SMALL, LARGE]
+        .iter()
+        .filter(|font| font.height == rows.len())
+        .find_map(|font| font.read(&rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws the letters back, the way the puzzles do, gap after the last one included
+    fn render(font: &Font, letters: &str) -> String {
+        (0..font.height)
+            .map(|row| {
+                let glyphs: Vec<String> = letters
+                    .chars()
+                    .map(|letter| {
+                        let glyph = font
+                            .glyphs
+                            .iter()
+                            .find(|(glyph, _)| *glyph == letter)
+                            .unwrap()
+                            .1[row];
+                        format!("{:.<1$}", glyph, font.width + font.gap)
+                    })
+                    .collect();
+                glyphs.concat() + "\n"
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_recognize_small() {
+        let image = "\
+.##..###.
+#..#.#..#
+#..#.###.
+####.#..#
+#..#.#..#
+#..#.###.
+";
+        assert_eq!(recognize(image), Some(String::from("AB")));
+    }
+
+    #[test]
+    fn test_recognize_small_with_trailing_gap() {
+        let image = render(&SMALL, "EHZFZHCZ")
+            .lines()
+            .map(|row| format!("{}.\n", row))
+            .collect::<String>();
+        assert_eq!(recognize(&image), Some(String::from("EHZFZHCZ")));
+    }
+
+    #[test]
+    fn test_recognize_small_i_and_y() {
+        // Y is as wide as a letter and its gap
+        let image = "\
+.###.#...#.###
+..#..#...#..#.
+..#...#.#...#.
+..#....#....#.
+..#....#....#.
+.###...#...###
+";
+        assert_eq!(recognize(image), Some(String::from("IYI")));
+    }
+
+    #[test]
+    fn test_recognize_every_glyph() {
+        for font in [SMALL, LARGE] {
+            let letters: String = font.glyphs.iter().map(|(letter, _)| *letter).collect();
+            assert_eq!(recognize(&render(&font, &letters)), Some(letters));
+        }
+    }
+
+    #[test]
+    fn test_recognize_unknown_glyph() {
+        let image = render(&SMALL, "AB").replacen(".##.", "#.#.", 1);
+        assert_eq!(recognize(&image), None);
+    }
+
+    #[test]
+    fn test_recognize_not_letters() {
+        assert_eq!(recognize(""), None);
+        assert_eq!(recognize("##..\n..##\n"), None);
+        assert_eq!(recognize("####\n#..\n###.\n#...\n#...\n####"), None);
+    }
+}
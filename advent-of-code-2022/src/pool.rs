@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+// Applies work to every item on up to jobs threads, handing the results over in
+// the order of the items, each one as soon as all those before it are done
+pub fn for_each_ordered<T, R, W, H>(items: &[T], jobs: usize, work: W, mut handle: H)
+where
+    T: Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    H: FnMut(R),
+{
+    // No need for threads when there's nothing to overlap
+    if jobs <= 1 || items.len() <= 1 {
+        items.iter().map(&work).for_each(handle);
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(items.len()) {
+            let sender = sender.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let item = match items.get(index) {
+                    Some(item) => item,
+                    None => break,
+                };
+                if sender.send((index, work(item))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some(result) = pending.remove(&expected) {
+                handle(result);
+                expected += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn collect(items: &[u64], jobs: usize) -> Vec<u64> {
+        let mut results = Vec::new();
+        for_each_ordered(
+            items,
+            jobs,
+            |item| {
+                // Later items finish first
+                thread::sleep(Duration::from_millis(20 - item * 2));
+                item * 10
+            },
+            |result| results.push(result),
+        );
+        results
+    }
+
+    #[test]
+    fn test_for_each_ordered_sequential() {
+        assert_eq!(collect(&[1, 2, 3], 1), vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn test_for_each_ordered_parallel() {
+        let items: Vec<u64> = (1..=8).collect();
+        assert_eq!(
+            collect(&items, 4),
+            (1..=8).map(|x| x * 10).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_for_each_ordered_more_jobs_than_items() {
+        assert_eq!(collect(&[5, 1], 16), vec![50, 10]);
+    }
+
+    #[test]
+    fn test_for_each_ordered_empty() {
+        assert_eq!(collect(&[], 4), Vec::<u64>::new());
+    }
+}
@@ -0,0 +1,244 @@
+use crate::config::Config;
+use crate::error::Error;
+use crate::http::Client;
+use crate::scaffold::ScaffoldError;
+use crate::solution::Part;
+
+#[derive(Debug)]
+pub enum PuzzleError {
+    Download(Box<ureq::Error>),
+    Fixture(ScaffoldError),
+    NoExample,
+}
+
+impl std::fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Download(_) => write!(f, "Unable to download the puzzle description"),
+            Self::Fixture(_) => write!(f, "Unable to write the example"),
+            Self::NoExample => write!(f, "No code block in the puzzle description"),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Download(error) => Some(error.as_ref()),
+            Self::Fixture(error) => Some(error),
+            Self::NoExample => None,
+        }
+    }
+}
+
+// The puzzle description, as served to the logged in user
+pub fn fetch(config: &Config, day: u8) -> Result<String, Error> {
+    let token = config.token().map_err(|source| Error::Config { source })?;
+    let path = format!("{}/{}/day/{}", config.base_url, config.year, day);
+    let cookie = format!("session={}", token);
+
+    Client::new(config)
+        .get(&path, &cookie)
+        .and_then(|response| {
+            response
+                .into_string()
+                .map_err(|error| Box::new(ureq::Error::from(error)))
+        })
+        .map_err(|source| Error::Puzzle {
+            day,
+            source: PuzzleError::Download(source),
+        })
+}
+
+// Only the few entities adventofcode.com uses
+fn unescape(html: &str) -> String {
+    html.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+// Markup inside text, like the <em> highlighting parts of an example
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    unescape(&text)
+}
+
+// One per part, the second showing once the first is solved
+fn articles(html: &str) -> Vec<&str> {
+    html.split("<article class=\"day-desc\">")
+        .skip(1)
+        .filter_map(|article| article.split_once("</article>"))
+        .map(|(article, _)| article)
+        .collect()
+}
+
+// Code blocks of the description, in order, the first one usually being the example
+pub fn code_blocks(html: &str) -> Vec<String> {
+    html.split("<pre><code>")
+        .skip(1)
+        .filter_map(|block| block.split_once("</code></pre>"))
+        .map(|(code, _)| strip_tags(code))
+        .collect()
+}
+
+// By habit, the answer to the example is the last emphasised code of a part
+fn emphasised_answer(article: &str) -> Option<String> {
+    let (start, open) = ["<code><em>", "<em><code>"]
+        .iter()
+        .filter_map(|open| Some((article.rfind(open)?, open.len())))
+        .max()?;
+    let answer = &article[start + open..];
+    let answer = strip_tags(&answer[..answer.find("</")?]);
+    (!answer.is_empty()).then_some(answer)
+}
+
+// Ends the text with as many line breaks as asked, unless nothing was written yet
+fn break_lines(text: &mut String, count: usize) {
+    while !text.is_empty() && !text.ends_with(&"\n".repeat(count)) {
+        text.push('\n');
+    }
+}
+
+// The description as text, paragraphs and code blocks separated by blank lines
+pub fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    for article in articles(html) {
+        let mut rest = article;
+        while let Some(start) = rest.find('<') {
+            let chunk = unescape(&rest[..start]);
+            // Markup is indented and spread on lines of its own
+            if !(chunk.trim().is_empty() && text.ends_with('\n')) {
+                text.push_str(&chunk);
+            }
+            let Some(end) = rest[start..].find('>') else {
+                break;
+            };
+            let tag = &rest[start + 1..start + end];
+            match tag.split_whitespace().next().unwrap_or_default() {
+                "h2" | "/h2" | "p" | "/p" | "pre" | "/pre" | "ul" | "/ul" => {
+                    break_lines(&mut text, 2)
+                }
+                "li" => {
+                    break_lines(&mut text, 1);
+                    text.push_str("- ");
+                }
+                _ => {}
+            }
+            rest = &rest[start + end + 1..];
+        }
+        text.push_str(&unescape(rest));
+        break_lines(&mut text, 2);
+    }
+
+    text.trim_end().to_string() + "\n"
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Example {
+    pub input: String,
+    pub answers: Vec<(Part, String)>,
+}
+
+impl Example {
+    // The first code block of part 1 is the input, and each part gives the answer for it
+    pub fn from_html(html: &str) -> Option<Self> {
+        let articles = articles(html);
+        let input = code_blocks(articles.first()?).into_iter().next()?;
+        let answers = Part::ALL
+            .iter()
+            .zip(&articles)
+            .filter_map(|(part, article)| Some((*part, emphasised_answer(article)?)))
+            .collect();
+
+        Some(Example { input, answers })
+    }
+
+    // In the format of fixtures/day_N.expected
+    pub fn expected(&self) -> String {
+        self.answers
+            .iter()
+            .map(|(part, answer)| format!("{}: {}\n", part, answer))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::read_fixture;
+
+    fn read_page(day: u8) -> String {
+        std::fs::read_to_string(format!("fixtures/pages/day_{}.html", day)).unwrap()
+    }
+
+    #[test]
+    fn test_strip_tags() {
+        assert_eq!(
+            strip_tags("a -&gt; <em>b</em> &amp;&amp; c&lt;d"),
+            "a -> b && c<d"
+        );
+    }
+
+    #[test]
+    fn test_code_blocks() {
+        let html = "<p>For example:</p>\n<pre><code>1000\n<em>2000</em>\n</code></pre>\n\
+                    <p>Then <code>24000</code>.</p>\n<pre><code>$ cd /\n</code></pre>";
+        assert_eq!(
+            code_blocks(html),
+            vec![String::from("1000\n2000\n"), String::from("$ cd /\n")]
+        );
+    }
+
+    #[test]
+    fn test_emphasised_answer() {
+        let html = "<p><code><em>A</em></code> then <em><code>B</code></em>, \
+                    <em>really</em> and <code>C</code>.</p>";
+        assert_eq!(emphasised_answer(html), Some(String::from("B")));
+        assert_eq!(emphasised_answer("<p><em>none</em></p>"), None);
+    }
+
+    #[test]
+    fn test_example_from_html_both_parts() {
+        let example = Example::from_html(&read_page(1)).unwrap();
+        assert_eq!(example.input, read_fixture(1));
+        assert_eq!(
+            example.expected(),
+            std::fs::read_to_string("fixtures/day_1.expected").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_example_from_html_part_one() {
+        let example = Example::from_html(&read_page(12)).unwrap();
+        assert_eq!(example.input, read_fixture(12));
+        assert_eq!(example.answers, vec![(Part::One, String::from("31"))]);
+    }
+
+    #[test]
+    fn test_example_from_html_none() {
+        assert_eq!(Example::from_html("<main><p>404</p></main>"), None);
+    }
+
+    #[test]
+    fn test_text() {
+        let html = "<article class=\"day-desc\"><h2>--- Day 0 ---</h2><p>Count <em>up</em>:</p>\
+                    <pre><code>1 &lt; 2\n</code></pre><ul><li>one</li><li>two</li></ul></article>\
+                    <p>Answer: <code>3</code></p>\
+                    <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2></article>";
+        assert_eq!(
+            text(html),
+            "--- Day 0 ---\n\nCount up:\n\n1 < 2\n\n- one\n- two\n\n--- Part Two ---\n"
+        );
+    }
+}
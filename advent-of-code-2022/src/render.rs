@@ -0,0 +1,197 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::grid::{Grid, Point};
+
+pub type Color = [u8; 3];
+
+// What a day makes of its input, drawn to help debugging it
+pub struct Render {
+    pub text: String,
+    pub image: Option<Image>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    // Every cell becomes a square of scale × scale pixels
+    pub fn from_grid(grid: &Grid<Color>, scale: usize) -> Self {
+        let (width, height) = (grid.columns() * scale, grid.rows() * scale);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                grid[Point {
+                    row: y / scale,
+                    column: x / scale,
+                }]
+            })
+            .collect();
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    // Binary PPM, which most image viewers and converters open
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let header = format!("P6\n{} {}\n255\n", self.width, self.height);
+        let mut bytes = header.into_bytes();
+        bytes.extend(self.pixels.iter().flatten());
+        bytes
+    }
+
+    // Uncompressed PNG, larger than it could be but readable by about anything
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGB, then the only compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        // Each row starts with its filter, none
+        let mut rows = Vec::new();
+        for row in self.pixels.chunks(self.width.max(1)) {
+            rows.push(0);
+            rows.extend(row.iter().flatten());
+        }
+
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut bytes, b"IHDR", &header);
+        chunk(&mut bytes, b"IDAT", &zlib_stored(&rows));
+        chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    // PNG when the path ends in .png, PPM otherwise
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let png = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+        match png {
+            true => fs::write(path, self.to_png()),
+            false => fs::write(path, self.to_ppm()),
+        }
+    }
+}
+
+fn chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB8_8320,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+// A zlib stream of deflate blocks kept as they are, at most 65535 bytes each
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut bytes = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = data.chunks(0xFFFF).collect();
+    for (index, block) in blocks.iter().enumerate() {
+        let last = index + 1 == blocks.len();
+        bytes.push(last as u8);
+        bytes.extend((block.len() as u16).to_le_bytes());
+        bytes.extend((!(block.len() as u16)).to_le_bytes());
+        bytes.extend(*block);
+    }
+    if blocks.is_empty() {
+        bytes.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    bytes.extend(((b << 16) | a).to_be_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_from_grid() {
+        let grid = Grid::parse("rg\ngr", |_, ch| match ch {
+            'r' => Ok([255, 0, 0]),
+            'g' => Ok([0, 255, 0]),
+            _ => Err("Unknown color"),
+        })
+        .unwrap();
+        let image = Image::from_grid(&grid, 2);
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[0], [255, 0, 0]);
+        assert_eq!(image.pixels[1], [255, 0, 0]);
+        assert_eq!(image.pixels[2], [0, 255, 0]);
+        assert_eq!(image.pixels[4], [255, 0, 0]);
+        assert_eq!(image.pixels[8], [0, 255, 0]);
+    }
+
+    #[test]
+    fn test_image_to_ppm() {
+        let image = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![[1, 2, 3], [4, 5, 6]],
+        };
+        assert_eq!(image.to_ppm(), b"P6\n2 1\n255\n\x01\x02\x03\x04\x05\x06");
+    }
+
+    #[test]
+    fn test_image_to_png() {
+        let image = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![[1, 2, 3], [4, 5, 6]],
+        };
+        let png = image.to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x02\0\0\0\x01\x08\x02"));
+        // The row, behind its filter, in a single stored block
+        let row = b"\0\x01\x02\x03\x04\x05\x06";
+        let idat = png.windows(4).position(|kind| kind == b"IDAT").unwrap();
+        assert_eq!(png[idat + 4..idat + 11], [0x78, 0x01, 1, 7, 0, 0xF8, 0xFF]);
+        assert_eq!(png[idat + 11..idat + 18], *row);
+        assert!(png.ends_with(b"\0\0\0\0IEND\xAE\x42\x60\x82"));
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_zlib_stored_many_blocks() {
+        let data = vec![7; 0x10000];
+        let bytes = zlib_stored(&data);
+        // Header, two blocks with theirs, and the checksum
+        assert_eq!(bytes.len(), 2 + 2 * 5 + data.len() + 4);
+        assert_eq!(bytes[2], 0);
+        assert_eq!(
+            bytes[2 + 5 + 0xFFFF..2 + 5 + 0xFFFF + 5],
+            [1, 1, 0, 0xFE, 0xFF]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::{Error, SolutionError};
+use crate::example::Verdict;
+use crate::ledger::Mark;
+use crate::solution::Part;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Json,
+    Ndjson,
+    Text,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct FormatParseError(String);
+
+impl std::fmt::Display for FormatParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid format: {}, expected text, json or ndjson",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for FormatParseError {}
+
+impl FromStr for Format {
+    type Err = FormatParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "text" => Ok(Format::Text),
+            _ => Err(FormatParseError(s.to_string())),
+        }
+    }
+}
+
+// Keep multi-line answers aligned with the first line
+pub fn indent_answer(answer: &str) -> String {
+    answer.trim_end().lines().collect::<Vec<_>>().join("\n\t\t")
+}
+
+// Quoted and escaped as a JSON string, newlines included
+pub fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn json_option(value: Option<String>) -> String {
+    value.unwrap_or_else(|| String::from("null"))
+}
+
+// How an answer was checked, against the example or against the ledger
+#[derive(Debug, Eq, PartialEq)]
+pub enum Check {
+    Example(Verdict),
+    Ledger(Mark),
+}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: Part,
+    pub answer: String,
+    pub check: Option<Check>,
+}
+
+impl PartReport {
+    fn passed(&self) -> bool {
+        !matches!(
+            self.check,
+            Some(Check::Example(Verdict::Fail(_)) | Check::Ledger(Mark::Regression))
+        )
+    }
+
+    fn json(&self) -> String {
+        let (verdict, expected, mark) = match &self.check {
+            Some(Check::Example(Verdict::Pass)) => (Some("pass"), None, None),
+            Some(Check::Example(Verdict::Fail(expected))) => {
+                (Some("fail"), Some(expected.as_str()), None)
+            }
+            Some(Check::Ledger(mark)) => (None, None, Some(mark)),
+            None => (None, None, None),
+        };
+        let mark = mark.map(|mark| match mark {
+            Mark::Regression => "regression",
+            Mark::Unknown => "unknown",
+            Mark::Verified => "verified",
+        });
+
+        format!(
+            "{{\"part\":{},\"answer\":{},\"verdict\":{},\"expected\":{},\"ledger\":{}}}",
+            self.part,
+            // Answers are kept as printed by the solution, trailing newline aside
+            json_string(self.answer.trim_end()),
+            json_option(verdict.map(json_string)),
+            json_option(expected.map(json_string)),
+            json_option(mark.map(json_string)),
+        )
+    }
+}
+
+pub struct DayReport {
+    pub day: u8,
+    pub elapsed: Duration,
+    pub result: Result<Vec<PartReport>, Error>,
+}
+
+impl DayReport {
+    pub fn passed(&self) -> bool {
+        match &self.result {
+            Ok(parts) => parts.iter().all(PartReport::passed),
+            Err(_) => false,
+        }
+    }
+
+    pub fn status(&self) -> &'static str {
+        match (&self.result, self.passed()) {
+            (
+                Err(Error::Solution {
+                    source: SolutionError::Panic(_),
+                    ..
+                }),
+                _,
+            ) => "panic",
+            (Err(Error::TimedOut { .. }), _) => "timeout",
+            (Err(_), _) => "error",
+            (Ok(_), false) => "fail",
+            (Ok(_), true) => "ok",
+        }
+    }
+
+    // Errors go to stderr, along the answers on stdout
+    pub fn print_text(&self) {
+        println!("Day {}, done in {:?}", self.day, self.elapsed);
+        match &self.result {
+            Ok(parts) => {
+                for part in parts {
+                    let answer = indent_answer(&part.answer);
+                    match &part.check {
+                        Some(Check::Example(Verdict::Pass)) => {
+                            println!("\tPart {}: {} (pass)", part.part, answer)
+                        }
+                        Some(Check::Example(Verdict::Fail(expected))) => {
+                            println!("\tPart {}: {} (fail)", part.part, answer);
+                            println!("\t\texpected {}", indent_answer(expected));
+                        }
+                        Some(Check::Ledger(mark)) => {
+                            println!("\tPart {}: {} {}", part.part, answer, mark)
+                        }
+                        None => println!("\tPart {}: {}", part.part, answer),
+                    }
+                }
+            }
+            Err(error) => eprintln!("\tError: {}", indent_answer(&error.report())),
+        }
+        println!();
+    }
+
+    pub fn json(&self) -> String {
+        let (parts, error) = match &self.result {
+            Ok(parts) => {
+                let parts: Vec<String> = parts.iter().map(PartReport::json).collect();
+                (format!("[{}]", parts.join(",")), None)
+            }
+            Err(error) => {
+                let mut causes = Vec::new();
+                let mut source = std::error::Error::source(error);
+                while let Some(cause) = source {
+                    causes.push(json_string(&cause.to_string()));
+                    source = cause.source();
+                }
+                let error = format!(
+                    "{{\"message\":{},\"causes\":[{}]}}",
+                    json_string(&error.to_string()),
+                    causes.join(",")
+                );
+                (String::from("[]"), Some(error))
+            }
+        };
+
+        format!(
+            "{{\"day\":{},\"status\":{},\"elapsed_ns\":{},\"parts\":{},\"error\":{}}}",
+            self.day,
+            json_string(self.status()),
+            self.elapsed.as_nanos(),
+            parts,
+            json_option(error)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(result: Result<Vec<PartReport>, Error>) -> DayReport {
+        DayReport {
+            day: 10,
+            elapsed: Duration::from_micros(15),
+            result,
+        }
+    }
+
+    #[test]
+    fn test_indent_answer_single_line() {
+        assert_eq!(indent_answer("24000"), "24000");
+    }
+
+    #[test]
+    fn test_indent_answer_multi_line() {
+        assert_eq!(indent_answer("##..\n..##\n"), "##..\n\t\t..##");
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("ndjson".parse::<Format>(), Ok(Format::Ndjson));
+        assert_eq!(
+            "xml".parse::<Format>(),
+            Err(FormatParseError(String::from("xml")))
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a \"b\"\\c"), r#""a \"b\"\\c""#);
+        assert_eq!(json_string("##..\n..##"), r###""##..\n..##""###);
+        assert_eq!(json_string("\u{1}"), r#""\u0001""#);
+    }
+
+    #[test]
+    fn test_day_report_json_multi_line() {
+        let day = report(Ok(vec![
+            PartReport {
+                part: Part::One,
+                answer: String::from("13140"),
+                check: Some(Check::Ledger(Mark::Verified)),
+            },
+            PartReport {
+                part: Part::Two,
+                answer: String::from("##..\n..##\n"),
+                check: Some(Check::Example(Verdict::Fail(String::from("#...")))),
+            },
+        ]));
+        assert!(!day.passed());
+        assert_eq!(
+            day.json(),
+            concat!(
+                r#"{"day":10,"status":"fail","elapsed_ns":15000,"parts":["#,
+                r#"{"part":1,"answer":"13140","verdict":null,"expected":null,"ledger":"verified"},"#,
+                r###"{"part":2,"answer":"##..\n..##","verdict":"fail","expected":"#...","ledger":null}"###,
+                r#"],"error":null}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_day_report_status_panic() {
+        let day = report(Err(Error::Solution {
+            day: 10,
+            part: Some(Part::Two),
+            source: SolutionError::Panic(crate::guard::Panic {
+                message: String::from("attempt to add with overflow"),
+                location: Some(String::from("src/day_10.rs:93:24")),
+            }),
+        }));
+        assert_eq!(day.status(), "panic");
+        assert!(day.json().contains(
+            r#""causes":["Panicked at src/day_10.rs:93:24: attempt to add with overflow"]"#
+        ));
+    }
+
+    #[test]
+    fn test_day_report_json_error() {
+        let day = report(Err(Error::Locked {
+            day: 10,
+            remaining: Duration::from_secs(65),
+        }));
+        assert_eq!(
+            day.json(),
+            concat!(
+                r#"{"day":10,"status":"error","elapsed_ns":15000,"parts":[],"#,
+                r#""error":{"message":"Day 10 is still locked, it unlocks in 1m 5s","causes":[]}}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::input::FIXTUREDIR;
+use crate::puzzle::Example;
+
+const SRCDIR: &str = "src";
+// Same as rustfmt, for the list of days imported by the registry
+const MAX_WIDTH: usize = 100;
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    Exists(PathBuf),
+    Io(PathBuf, io::Error),
+    // The file doesn't look like it used to, and is left alone
+    Unrecognized(PathBuf),
+}
+
+impl std::fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exists(path) => write!(f, "{} already exists", path.display()),
+            Self::Io(path, _) => write!(f, "Unable to access {}", path.display()),
+            Self::Unrecognized(path) => {
+                write!(
+                    f,
+                    "Unable to find where to add the day in {}",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, error) => Some(error),
+            Self::Exists(_) | Self::Unrecognized(_) => None,
+        }
+    }
+}
+
+// A day that parses its input into lines and solves nothing yet, tests included
+pub fn module(day: u8) -> String {
+    format!(
+        r#"use std::str::FromStr;
+
+use crate::error::{{ParseError, SolutionError}};
+use crate::solution::Solution;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Notes {{
+    lines: Vec<String>,
+}}
+
+impl FromStr for Notes {{
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {{
+        if s.trim().is_empty() {{
+            return Err(ParseError::new("Empty input"));
+        }}
+
+        Ok(Notes {{
+            lines: s.lines().map(String::from).collect(),
+        }})
+    }}
+}}
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {{
+    type Input = Notes;
+    type AnswerOne = usize;
+    type AnswerTwo = usize;
+    type Error = SolutionError;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error> {{
+        Ok(input.parse::<Notes>()?)
+    }}
+
+    // Nothing is made of a line yet, so there is no answer
+    fn part_one(notes: &Notes) -> Result<usize, Self::Error> {{
+        notes
+            .lines
+            .iter()
+            .map(|_| None::<usize>)
+            .sum::<Option<usize>>()
+            .ok_or(SolutionError::NoAnswer)
+    }}
+
+    fn part_two(notes: &Notes) -> Result<usize, Self::Error> {{
+        notes
+            .lines
+            .iter()
+            .map(|_| None::<usize>)
+            .sum::<Option<usize>>()
+            .ok_or(SolutionError::NoAnswer)
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+    use crate::solution::read_fixture;
+
+    #[test]
+    fn test_notes_from_str_empty_err() {{
+        let result = "".parse::<Notes>();
+        assert_eq!(result, Err(ParseError::new("Empty input")));
+    }}
+
+    #[test]
+    fn test_solve_unsolved_err() {{
+        let notes = Puzzle::parse("1\n2").unwrap();
+        assert_eq!(Puzzle::part_one(&notes), Err(SolutionError::NoAnswer));
+        assert_eq!(Puzzle::part_two(&notes), Err(SolutionError::NoAnswer));
+    }}
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_solve_part_1_ok() {{
+        let notes = Puzzle::parse(&read_fixture({day})).unwrap();
+        let result = Puzzle::part_one(&notes).unwrap();
+        assert_eq!(result, 0);
+    }}
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_solve_part_2_ok() {{
+        let notes = Puzzle::parse(&read_fixture({day})).unwrap();
+        let result = Puzzle::part_two(&notes).unwrap();
+        assert_eq!(result, 0);
+    }}
+}}
+"#
+    )
+}
+
+// Adds "mod day_N;" among the other days, in the order rustfmt keeps them
+fn add_mod(main: &str, day: u8) -> Option<String> {
+    let mut lines: Vec<&str> = main.lines().collect();
+    let first = lines.iter().position(|line| line.starts_with("mod day_"))?;
+    let count = lines[first..]
+        .iter()
+        .take_while(|line| line.starts_with("mod day_"))
+        .count();
+
+    let line = format!("mod day_{};", day);
+    let mut days: Vec<&str> = lines.drain(first..first + count).collect();
+    days.push(&line);
+    days.sort_unstable_by_key(|line| line.trim_end_matches(';'));
+    lines.splice(first..first, days);
+
+    Some(lines.join("\n") + "\n")
+}
+
+// Imports the day and adds it to the registry, which stays sorted by day
+fn register(solution: &str, day: u8) -> Option<String> {
+    let import_start = solution.find("use crate::{")?;
+    let import_end = import_start + solution[import_start..].find("};")? + 2;
+    let mut modules: Vec<String> = solution[import_start + "use crate::{".len()..import_end - 2]
+        .split(',')
+        .map(|module| module.trim().to_string())
+        .filter(|module| !module.is_empty())
+        .collect();
+    if !modules.iter().all(|module| module.starts_with("day_")) {
+        return None;
+    }
+    modules.push(format!("day_{}", day));
+    modules.sort_unstable();
+
+    let mut import = String::from("use crate::{");
+    let single_line = format!("{}{}}};", import, modules.join(", "));
+    if single_line.len() <= MAX_WIDTH {
+        import = single_line;
+    } else {
+        let mut line = String::from("   ");
+        for module in &modules {
+            if line.len() + module.len() + 2 > MAX_WIDTH {
+                import.push_str(&format!("\n{}", line));
+                line = String::from("   ");
+            }
+            line.push_str(&format!(" {},", module));
+        }
+        import.push_str(&format!("\n{}\n}};", line));
+    }
+
+    let registry_start = solution.find("pub const REGISTRY: [Day; ")?;
+    let registry_end = registry_start + solution[registry_start..].find("];")?;
+    let header_end = registry_start + solution[registry_start..].find('\n')?;
+    let mut entries: Vec<(u8, String)> = solution[header_end + 1..registry_end]
+        .lines()
+        .map(|line| {
+            let number = line
+                .trim()
+                .strip_prefix("Day::new::<day_")?
+                .split(':')
+                .next()?;
+            Some((number.parse().ok()?, line.to_string()))
+        })
+        .collect::<Option<_>>()?;
+    entries.push((
+        day,
+        format!("    Day::new::<day_{}::Puzzle>({}),", day, day),
+    ));
+    entries.sort_unstable_by_key(|(number, _)| *number);
+    let entries: Vec<String> = entries.into_iter().map(|(_, line)| line).collect();
+    let registry = format!(
+        "pub const REGISTRY: [Day; {}] = [\n{}\n",
+        entries.len(),
+        entries.join("\n")
+    );
+
+    Some(format!(
+        "{}{}{}{}{}",
+        &solution[..import_start],
+        import,
+        &solution[import_end..registry_start],
+        registry,
+        &solution[registry_end..]
+    ))
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    fs::read_to_string(path).map_err(|error| ScaffoldError::Io(path.to_path_buf(), error))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), ScaffoldError> {
+    fs::write(path, content).map_err(|error| ScaffoldError::Io(path.to_path_buf(), error))
+}
+
+fn fixture_paths(root: &Path, day: u8) -> [PathBuf; 2] {
+    let fixtures = root.join(FIXTUREDIR);
+    [
+        fixtures.join(format!("day_{}", day)),
+        fixtures.join(format!("day_{}.expected", day)),
+    ]
+}
+
+// The example input, and its expected answers when the description gave any
+pub fn write_example(
+    root: &Path,
+    day: u8,
+    example: &Example,
+    overwrite: bool,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let [input_path, expected_path] = fixture_paths(root, day);
+    let mut files = vec![(input_path, example.input.clone())];
+    if !example.answers.is_empty() {
+        files.push((expected_path, example.expected()));
+    }
+
+    if let Some((path, _)) = files.iter().find(|(path, _)| !overwrite && path.exists()) {
+        return Err(ScaffoldError::Exists(path.clone()));
+    }
+
+    let fixtures = root.join(FIXTUREDIR);
+    fs::create_dir_all(&fixtures).map_err(|error| ScaffoldError::Io(fixtures, error))?;
+    for (path, content) in &files {
+        write(path, content)?;
+    }
+
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+// Everything is checked before anything is written, and nothing is overwritten
+pub fn create(
+    root: &Path,
+    day: u8,
+    example: Option<&Example>,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let module_path = root.join(SRCDIR).join(format!("day_{}.rs", day));
+    let [input_path, expected_path] = fixture_paths(root, day);
+    let main_path = root.join(SRCDIR).join("main.rs");
+    let solution_path = root.join(SRCDIR).join("solution.rs");
+
+    for path in [&module_path, &input_path, &expected_path] {
+        if path.exists() {
+            return Err(ScaffoldError::Exists(path.clone()));
+        }
+    }
+
+    let main = add_mod(&read(&main_path)?, day)
+        .ok_or_else(|| ScaffoldError::Unrecognized(main_path.clone()))?;
+    let solution = register(&read(&solution_path)?, day)
+        .ok_or_else(|| ScaffoldError::Unrecognized(solution_path.clone()))?;
+
+    let empty = Example {
+        input: String::new(),
+        answers: Vec::new(),
+    };
+    let mut created = write_example(root, day, example.unwrap_or(&empty), false)?;
+    write(&module_path, &module(day))?;
+    write(&main_path, &main)?;
+    write(&solution_path, &solution)?;
+
+    created.extend([module_path, main_path, solution_path]);
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Part;
+
+    const MAIN: &str = "use std::env;
+
+mod cache;
+mod day_1;
+mod day_10;
+mod day_2;
+mod error;
+
+fn main() {}
+";
+
+    const SOLUTION: &str = "use crate::error::Error;
+use crate::{day_1, day_10, day_2};
+
+pub const REGISTRY: [Day; 3] = [
+    Day::new::<day_1::Puzzle>(1),
+    Day::new::<day_2::Puzzle>(2),
+    Day::new::<day_10::Puzzle>(10),
+];
+
+pub fn days() {}
+";
+
+    // As many days as it takes for the imports to wrap
+    const WIDE_SOLUTION: &str = "use crate::{
+    day_1, day_10, day_11, day_12, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9,
+};
+
+pub const REGISTRY: [Day; 12] = [
+    Day::new::<day_1::Puzzle>(1),
+    Day::new::<day_2::Puzzle>(2),
+    Day::new::<day_3::Puzzle>(3),
+    Day::new::<day_4::Puzzle>(4),
+    Day::new::<day_5::Puzzle>(5),
+    Day::new::<day_6::Puzzle>(6),
+    Day::new::<day_7::Puzzle>(7),
+    Day::new::<day_8::Puzzle>(8),
+    Day::new::<day_9::Puzzle>(9),
+    Day::new::<day_10::Puzzle>(10),
+    Day::new::<day_11::Puzzle>(11),
+    Day::new::<day_12::Puzzle>(12),
+];
+";
+
+    #[test]
+    fn test_add_mod() {
+        let result = add_mod(MAIN, 3).unwrap();
+        assert!(result
+            .contains("mod cache;\nmod day_1;\nmod day_10;\nmod day_2;\nmod day_3;\nmod error;\n"));
+    }
+
+    #[test]
+    fn test_register() {
+        let result = register(SOLUTION, 3).unwrap();
+        assert_eq!(
+            result,
+            "use crate::error::Error;
+use crate::{day_1, day_10, day_2, day_3};
+
+pub const REGISTRY: [Day; 4] = [
+    Day::new::<day_1::Puzzle>(1),
+    Day::new::<day_2::Puzzle>(2),
+    Day::new::<day_3::Puzzle>(3),
+    Day::new::<day_10::Puzzle>(10),
+];
+
+pub fn days() {}
+"
+        );
+    }
+
+    #[test]
+    fn test_register_wraps_imports() {
+        let result = register(WIDE_SOLUTION, 13).unwrap();
+        assert!(result.contains(
+            "use crate::{
+    day_1, day_10, day_11, day_12, day_13, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9,
+};"
+        ));
+        assert!(result.contains("    Day::new::<day_13::Puzzle>(13),\n];"));
+        assert!(result.contains("pub const REGISTRY: [Day; 13] = ["));
+    }
+
+    #[test]
+    fn test_write_example() {
+        let root = std::env::temp_dir().join(format!("aoc-example-{}", std::process::id()));
+        let example = Example {
+            input: String::from("Sabqponm\n"),
+            answers: Vec::new(),
+        };
+        let first = write_example(&root, 12, &example, false);
+        let again = write_example(&root, 12, &example, false);
+        let forced = write_example(&root, 12, &example, true);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(first.unwrap(), vec![root.join(FIXTUREDIR).join("day_12")]);
+        assert!(matches!(again, Err(ScaffoldError::Exists(_))));
+        assert!(forced.is_ok());
+    }
+
+    #[test]
+    fn test_register_unrecognized() {
+        assert_eq!(register("pub fn days() {}", 3), None);
+    }
+
+    #[test]
+    fn test_create() {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join(SRCDIR)).unwrap();
+        fs::write(root.join(SRCDIR).join("main.rs"), MAIN).unwrap();
+        fs::write(root.join(SRCDIR).join("solution.rs"), SOLUTION).unwrap();
+
+        let example = Example {
+            input: String::from("1\n2\n"),
+            answers: vec![(Part::One, String::from("3"))],
+        };
+        let created = create(&root, 13, Some(&example));
+        let again = create(&root, 13, None);
+        let fixture = fs::read_to_string(root.join(FIXTUREDIR).join("day_13"));
+        let expected = fs::read_to_string(root.join(FIXTUREDIR).join("day_13.expected"));
+        let module = fs::read_to_string(root.join(SRCDIR).join("day_13.rs"));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(created.unwrap().len(), 5);
+        assert!(matches!(again, Err(ScaffoldError::Exists(_))));
+        assert_eq!(fixture.unwrap(), "1\n2\n");
+        assert_eq!(expected.unwrap(), "1: 3\n");
+        assert!(module.unwrap().contains("read_fixture(13)"));
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Puzzles unlock at midnight US/Eastern, which is UTC-5 all December long
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+// Days since 1970-01-01, from Howard Hinnant's civil calendar algorithms
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146097 + day_of_era - 719468
+}
+
+pub fn unlock_time(year: u16, day: u8) -> SystemTime {
+    let days = days_from_civil(year as i64, 12, day as i64) as u64;
+    UNIX_EPOCH + Duration::from_secs(days * 86400 + UNLOCK_HOUR_UTC * 3600)
+}
+
+// Nothing left to wait once the puzzle is out
+pub fn until_unlock(year: u16, day: u8, now: SystemTime) -> Option<Duration> {
+    unlock_time(year, day)
+        .duration_since(now)
+        .ok()
+        .filter(|remaining| !remaining.is_zero())
+}
+
+// Rounded down to the second, like "2d 3h 0m 12s"
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let units = [
+        (seconds / 86400, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ];
+
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(value, unit)| *value == 0 && *unit != "s")
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_from_civil() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(2022, 12, 1), 19327);
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        // 2022-12-01T05:00:00Z
+        let expected = UNIX_EPOCH + Duration::from_secs(1669870800);
+        assert_eq!(unlock_time(2022, 1), expected);
+        assert_eq!(
+            unlock_time(2022, 25),
+            expected + Duration::from_secs(24 * 86400)
+        );
+    }
+
+    #[test]
+    fn test_until_unlock() {
+        let unlock = unlock_time(2022, 3);
+        assert_eq!(
+            until_unlock(2022, 3, unlock - Duration::from_secs(90)),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(until_unlock(2022, 3, unlock + Duration::from_secs(1)), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(0)), "0s");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1m 5s");
+        assert_eq!(
+            format_duration(Duration::from_secs(2 * 86400 + 3 * 3600 + 12)),
+            "2d 3h 0m 12s"
+        );
+    }
+}
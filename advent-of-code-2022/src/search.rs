@@ -0,0 +1,212 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::cancel;
+
+// A route from its first node to its last, and what taking it costs
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Path<N> {
+    pub nodes: Vec<N>,
+    pub cost: u32,
+}
+
+impl<N> Path<N> {
+    pub fn steps(&self) -> usize {
+        self.nodes.len().saturating_sub(1)
+    }
+}
+
+fn unwind<N: Copy + Eq + Hash>(predecessors: &HashMap<N, N>, goal: N, cost: u32) -> Path<N> {
+    let mut nodes = vec![goal];
+    let mut current = goal;
+    while let Some(&predecessor) = predecessors.get(&current) {
+        nodes.push(predecessor);
+        current = predecessor;
+    }
+    nodes.reverse();
+    Path { nodes, cost }
+}
+
+// Every step costs 1, so the first path reaching the goal is a shortest one
+pub fn breadth_first<N, F, I>(start: N, goal: N, mut neighbours: F) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut predecessors = HashMap::new();
+    let mut visited = HashSet::from([start]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, cost)) = queue.pop_front() {
+        if node == goal {
+            return Some(unwind(&predecessors, goal, cost));
+        }
+        if cancel::cancelled() {
+            return None;
+        }
+
+        for next in neighbours(node) {
+            if visited.insert(next) {
+                predecessors.insert(next, node);
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    None
+}
+
+// Steps to every node reachable from any of the starts, in a single search
+pub fn distances<N, S, F, I>(starts: S, mut neighbours: F) -> HashMap<N, u32>
+where
+    N: Copy + Eq + Hash,
+    S: IntoIterator<Item = N>,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if distances.insert(start, 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        if cancel::cancelled() {
+            break;
+        }
+
+        let distance = distances[&node] + 1;
+        for next in neighbours(node) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+// Cheapest first, each edge coming with the cost of taking it
+pub fn dijkstra<N, F, I>(start: N, goal: N, edges: F) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = (N, u32)>,
+{
+    a_star(start, goal, edges, |_| 0)
+}
+
+// Dijkstra, guided by an estimate of the cost left to the goal. The estimate must
+// never exceed the actual cost, nor drop by more than the cost of a step
+pub fn a_star<N, F, I, H>(start: N, goal: N, mut edges: F, mut estimate: H) -> Option<Path<N>>
+where
+    N: Copy + Eq + Hash + Ord,
+    F: FnMut(N) -> I,
+    I: IntoIterator<Item = (N, u32)>,
+    H: FnMut(N) -> u32,
+{
+    let mut costs = HashMap::from([(start, 0)]);
+    let mut predecessors = HashMap::new();
+    let mut done = HashSet::new();
+    let mut queue = BinaryHeap::from([Reverse((estimate(start), start))]);
+
+    while let Some(Reverse((_, node))) = queue.pop() {
+        let cost = costs[&node];
+        if node == goal {
+            return Some(unwind(&predecessors, goal, cost));
+        }
+        if cancel::cancelled() {
+            return None;
+        }
+        // Queued again since, for less
+        if !done.insert(node) {
+            continue;
+        }
+
+        for (next, step) in edges(node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_none_or(|known| next_cost < *known) {
+                costs.insert(next, next_cost);
+                predecessors.insert(next, node);
+                queue.push(Reverse((next_cost + estimate(next), next)));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1 → 2 → 4 costs 1 + 5, 1 → 3 → 4 costs 2 + 2, and 5 is out of reach
+    fn edges(node: u8) -> Vec<(u8, u32)> {
+        match node {
+            1 => vec![(2, 1), (3, 2)],
+            2 => vec![(4, 5)],
+            3 => vec![(4, 2)],
+            5 => vec![(1, 1)],
+            _ => vec![],
+        }
+    }
+
+    fn neighbours(node: u8) -> impl Iterator<Item = u8> {
+        edges(node).into_iter().map(|(next, _)| next)
+    }
+
+    #[test]
+    fn test_breadth_first() {
+        let path = breadth_first(1, 4, neighbours).unwrap();
+        assert_eq!(path.nodes, vec![1, 2, 4]);
+        assert_eq!((path.steps(), path.cost), (2, 2));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let path = dijkstra(1, 4, edges).unwrap();
+        assert_eq!(path.nodes, vec![1, 3, 4]);
+        assert_eq!((path.steps(), path.cost), (2, 4));
+    }
+
+    #[test]
+    fn test_a_star() {
+        let estimate = |node: u8| if node == 4 { 0 } else { 1 };
+        let path = a_star(1, 4, edges, estimate).unwrap();
+        assert_eq!(path, dijkstra(1, 4, edges).unwrap());
+    }
+
+    #[test]
+    fn test_distances() {
+        let result = distances([1], neighbours);
+        assert_eq!(result, HashMap::from([(1, 0), (2, 1), (3, 1), (4, 2)]));
+    }
+
+    #[test]
+    fn test_distances_many_starts() {
+        let result = distances([5, 3, 3], neighbours);
+        assert_eq!(
+            result,
+            HashMap::from([(5, 0), (3, 0), (1, 1), (4, 1), (2, 2)])
+        );
+    }
+
+    #[test]
+    fn test_search_start_is_goal() {
+        let path = dijkstra(3, 3, edges).unwrap();
+        assert_eq!((path.nodes, path.cost), (vec![3], 0));
+        assert_eq!(breadth_first(3, 3, neighbours).unwrap().steps(), 0);
+    }
+
+    #[test]
+    fn test_search_unreachable() {
+        assert_eq!(breadth_first(1, 5, neighbours), None);
+        assert_eq!(dijkstra(4, 1, edges), None);
+    }
+}
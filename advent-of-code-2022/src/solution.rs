@@ -0,0 +1,250 @@
+use std::str::FromStr;
+
+use crate::error::{Error, SolutionError};
+use crate::guard;
+use crate::render::Render;
+use crate::{
+    day_1, day_10, day_11, day_12, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9,
+};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct PartParseError(String);
+
+impl std::fmt::Display for PartParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid part: {}, expected 1 or 2", self.0)
+    }
+}
+
+impl std::error::Error for PartParseError {}
+
+impl FromStr for Part {
+    type Err = PartParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(PartParseError(s.to_string())),
+        }
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+// Every day parses its input once and then solves both parts from it
+pub trait Solution {
+    type Input;
+    type AnswerOne: std::fmt::Display;
+    type AnswerTwo: std::fmt::Display;
+    type Error: Into<SolutionError>;
+
+    fn parse(input: &str) -> Result<Self::Input, Self::Error>;
+    fn part_one(input: &Self::Input) -> Result<Self::AnswerOne, Self::Error>;
+    fn part_two(input: &Self::Input) -> Result<Self::AnswerTwo, Self::Error>;
+
+    // Days can draw what they make of their input, for the render command
+    fn render(_input: &Self::Input) -> Option<Render> {
+        None
+    }
+}
+
+// Parsed input of any day, with the day’s types erased so the runner can hold them together
+pub trait Solved {
+    fn solve(&self, part: Part) -> Result<String, Error>;
+    fn render(&self) -> Option<Render>;
+}
+
+struct ParsedInput<S: Solution> {
+    day: u8,
+    input: S::Input,
+}
+
+// A panicking day fails like any other, and leaves the other days be
+impl<S: Solution> Solved for ParsedInput<S> {
+    fn solve(&self, part: Part) -> Result<String, Error> {
+        let answer = guard::catch(|| match part {
+            Part::One => S::part_one(&self.input).map(|answer| answer.to_string()),
+            Part::Two => S::part_two(&self.input).map(|answer| answer.to_string()),
+        });
+
+        let source = match answer {
+            Ok(Ok(answer)) => return Ok(answer),
+            Ok(Err(error)) => error.into(),
+            Err(panic) => SolutionError::Panic(panic),
+        };
+
+        Err(Error::Solution {
+            day: self.day,
+            part: Some(part),
+            source,
+        })
+    }
+
+    fn render(&self) -> Option<Render> {
+        S::render(&self.input)
+    }
+}
+
+type ParseFn = fn(u8, &str) -> Result<Box<dyn Solved>, Error>;
+
+fn parse<S: Solution + 'static>(day: u8, input: &str) -> Result<Box<dyn Solved>, Error> {
+    let source = match guard::catch(|| S::parse(input)) {
+        Ok(Ok(input)) => return Ok(Box::new(ParsedInput::<S> { day, input })),
+        Ok(Err(error)) => error.into(),
+        Err(panic) => SolutionError::Panic(panic),
+    };
+
+    Err(Error::Solution {
+        day,
+        part: None,
+        source,
+    })
+}
+
+pub struct Day {
+    pub number: u8,
+    parse: ParseFn,
+}
+
+impl Day {
+    const fn new<S: Solution + 'static>(number: u8) -> Self {
+        Day {
+            number,
+            parse: parse::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Solved>, Error> {
+        (self.parse)(self.number, input)
+    }
+}
+
+pub const REGISTRY: [Day; 12] = [
+    Day::new::<day_1::Puzzle>(1),
+    Day::new::<day_2::Puzzle>(2),
+    Day::new::<day_3::Puzzle>(3),
+    Day::new::<day_4::Puzzle>(4),
+    Day::new::<day_5::Puzzle>(5),
+    Day::new::<day_6::Puzzle>(6),
+    Day::new::<day_7::Puzzle>(7),
+    Day::new::<day_8::Puzzle>(8),
+    Day::new::<day_9::Puzzle>(9),
+    Day::new::<day_10::Puzzle>(10),
+    Day::new::<day_11::Puzzle>(11),
+    Day::new::<day_12::Puzzle>(12),
+];
+
+pub fn days() -> Vec<u8> {
+    REGISTRY.iter().map(|day| day.number).collect()
+}
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    REGISTRY.iter().find(|day| day.number == number)
+}
+
+#[cfg(test)]
+pub fn read_fixture(day: u8) -> String {
+    let file_path = std::path::PathBuf::from(format!("{}/day_{}", "fixtures", day));
+    std::fs::read_to_string(file_path).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_is_sorted_and_unique() {
+        let numbers = days();
+        let mut sorted = numbers.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(numbers, sorted);
+    }
+
+    #[test]
+    fn test_registry_find() {
+        assert_eq!(find(12).map(|day| day.number), Some(12));
+        assert!(find(25).is_none());
+    }
+
+    #[test]
+    fn test_day_parse_and_solve() {
+        let parsed = find(1).unwrap().parse(&read_fixture(1)).unwrap();
+        assert_eq!(parsed.solve(Part::One).unwrap(), "24000");
+        assert_eq!(parsed.solve(Part::Two).unwrap(), "45000");
+    }
+
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input = Vec<u8>;
+        type AnswerOne = u8;
+        type AnswerTwo = u8;
+        type Error = SolutionError;
+
+        fn parse(input: &str) -> Result<Self::Input, Self::Error> {
+            Ok(input.bytes().collect())
+        }
+
+        fn part_one(input: &Self::Input) -> Result<u8, Self::Error> {
+            Ok(input[0])
+        }
+
+        fn part_two(input: &Self::Input) -> Result<u8, Self::Error> {
+            Ok(input.iter().find(|byte| **byte > b'z').copied().unwrap())
+        }
+    }
+
+    #[test]
+    fn test_day_solve_panic() {
+        let parsed = Day::new::<Broken>(7).parse("abc").unwrap();
+        assert_eq!(parsed.solve(Part::One).unwrap(), "97");
+        match parsed.solve(Part::Two) {
+            Err(Error::Solution {
+                day: 7,
+                part: Some(Part::Two),
+                source: SolutionError::Panic(panic),
+            }) => {
+                assert!(panic.message.contains("`None`"));
+                assert!(panic.location.unwrap().starts_with("src/solution.rs:"));
+            }
+            result => panic!("Expected a panic, got {:?}", result.err()),
+        }
+    }
+
+    #[test]
+    fn test_day_render() {
+        let parsed = find(1).unwrap().parse(&read_fixture(1)).unwrap();
+        assert!(parsed.render().is_none());
+    }
+
+    #[test]
+    fn test_day_parse_err() {
+        let result = find(1).unwrap().parse("BOOM");
+        assert!(matches!(
+            result,
+            Err(Error::Solution {
+                day: 1,
+                part: None,
+                source: SolutionError::Parse(_)
+            })
+        ));
+    }
+}
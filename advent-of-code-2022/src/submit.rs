@@ -0,0 +1,294 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::http::Client;
+use crate::ledger::Rejection;
+use crate::solution::Part;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    AlreadySolved,
+    Correct,
+    RateLimited(Duration),
+    TooHigh,
+    TooLow,
+    Wrong,
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlreadySolved => write!(f, "Already solved"),
+            Self::Correct => write!(f, "Correct"),
+            Self::RateLimited(wait) => write!(f, "Rate-limited, wait {}s", wait.as_secs()),
+            Self::TooHigh => write!(f, "Too high"),
+            Self::TooLow => write!(f, "Too low"),
+            Self::Wrong => write!(f, "Wrong"),
+        }
+    }
+}
+
+impl Outcome {
+    // Short and stable name used in the submission log
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AlreadySolved => "already-solved",
+            Self::Correct => "correct",
+            Self::RateLimited(_) => "rate-limited",
+            Self::TooHigh => "too-high",
+            Self::TooLow => "too-low",
+            Self::Wrong => "wrong",
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct OutcomeParseError(String);
+
+impl std::fmt::Display for OutcomeParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown outcome {:?}", self.0)
+    }
+}
+
+// The wait time isn't worth keeping once it's over
+impl FromStr for Outcome {
+    type Err = OutcomeParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "already-solved" => Ok(Self::AlreadySolved),
+            "correct" => Ok(Self::Correct),
+            "rate-limited" => Ok(Self::RateLimited(Duration::ZERO)),
+            "too-high" => Ok(Self::TooHigh),
+            "too-low" => Ok(Self::TooLow),
+            "wrong" => Ok(Self::Wrong),
+            _ => Err(OutcomeParseError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SubmitError {
+    Http(Box<ureq::Error>),
+    KnownWrong(Rejection),
+    MultiLineAnswer,
+    UnknownResponse(String),
+}
+
+impl std::fmt::Display for SubmitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(_) => write!(f, "Request failed"),
+            Self::KnownWrong(rejection) => write!(f, "Refusing to submit: {}", rejection),
+            Self::MultiLineAnswer => write!(f, "Multi-line answers can't be submitted"),
+            Self::UnknownResponse(text) => write!(f, "Unexpected response: {:?}", text),
+        }
+    }
+}
+
+impl std::error::Error for SubmitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Http(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+// Durations are spelled like "1m 5s" or "34s"
+fn parse_wait(text: &str) -> Option<Duration> {
+    let (_, rest) = text.split_once("You have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+
+    wait.split_whitespace()
+        .map(|amount| {
+            let unit = amount.chars().last()?;
+            let value = amount[..amount.len() - 1].parse::<u64>().ok()?;
+            match unit {
+                'h' => Some(value * 3600),
+                'm' => Some(value * 60),
+                's' => Some(value),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+// The answer page is plain HTML, the verdict being the text of its <article>
+fn article_text(html: &str) -> &str {
+    let start = html
+        .find("<article")
+        .and_then(|start| html[start..].find('>').map(|end| start + end + 1))
+        .unwrap_or(0);
+    let end = html[start..]
+        .find("</article>")
+        .map(|end| start + end)
+        .unwrap_or(html.len());
+
+    &html[start..end]
+}
+
+pub fn parse_response(html: &str) -> Result<Outcome, SubmitError> {
+    let text = article_text(html);
+
+    if text.contains("That's the right answer") {
+        Ok(Outcome::Correct)
+    } else if text.contains("your answer is too high") {
+        Ok(Outcome::TooHigh)
+    } else if text.contains("your answer is too low") {
+        Ok(Outcome::TooLow)
+    } else if text.contains("That's not the right answer") {
+        Ok(Outcome::Wrong)
+    } else if text.contains("You gave an answer too recently") {
+        let wait = parse_wait(text).unwrap_or_default();
+        Ok(Outcome::RateLimited(wait))
+    } else if text.contains("Did you already complete it?") {
+        Ok(Outcome::AlreadySolved)
+    } else {
+        Err(SubmitError::UnknownResponse(text.trim().to_string()))
+    }
+}
+
+pub struct Submitter {
+    pub client: Client,
+    pub base_url: String,
+    pub year: u16,
+    pub token: String,
+}
+
+impl Submitter {
+    pub fn submit(&self, day: u8, part: Part, answer: &str) -> Result<Outcome, SubmitError> {
+        if answer.trim_end().contains('\n') {
+            return Err(SubmitError::MultiLineAnswer);
+        }
+
+        let path = format!("{}/{}/day/{}/answer", self.base_url, self.year, day);
+        let cookie = format!("session={}", self.token);
+        let level = part.to_string();
+        let body = self
+            .client
+            .post_form(
+                &path,
+                &cookie,
+                &[("level", level.as_str()), ("answer", answer.trim())],
+            )
+            .and_then(|response| {
+                response
+                    .into_string()
+                    .map_err(|error| Box::new(ureq::Error::from(error)))
+            })
+            .map_err(SubmitError::Http)?;
+
+        parse_response(&body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::mock::{self, Reply};
+
+    fn page(article: &str) -> String {
+        format!(
+            "<html><body><main><article><p>{}</p></article></main></body></html>",
+            article
+        )
+    }
+
+    #[test]
+    fn test_parse_response_correct() {
+        let html = page("That's the right answer!  You are <em>one gold star</em> closer.");
+        assert_eq!(parse_response(&html).unwrap(), Outcome::Correct);
+    }
+
+    #[test]
+    fn test_parse_response_too_high() {
+        let html = page("That's not the right answer; your answer is too high.");
+        assert_eq!(parse_response(&html).unwrap(), Outcome::TooHigh);
+    }
+
+    #[test]
+    fn test_parse_response_too_low() {
+        let html = page("That's not the right answer; your answer is too low.");
+        assert_eq!(parse_response(&html).unwrap(), Outcome::TooLow);
+    }
+
+    #[test]
+    fn test_parse_response_wrong() {
+        let html = page("That's not the right answer.  If you're stuck, make sure...");
+        assert_eq!(parse_response(&html).unwrap(), Outcome::Wrong);
+    }
+
+    #[test]
+    fn test_parse_response_rate_limited() {
+        let html = page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 5s left to wait.");
+        assert_eq!(
+            parse_response(&html).unwrap(),
+            Outcome::RateLimited(Duration::from_secs(65))
+        );
+    }
+
+    #[test]
+    fn test_parse_response_already_solved() {
+        let html =
+            page("You don't seem to be solving the right level.  Did you already complete it?");
+        assert_eq!(parse_response(&html).unwrap(), Outcome::AlreadySolved);
+    }
+
+    #[test]
+    fn test_parse_response_unknown_err() {
+        let result = parse_response(&page("Puzzle inputs differ by user."));
+        assert!(matches!(result, Err(SubmitError::UnknownResponse(_))));
+    }
+
+    #[test]
+    fn test_outcome_from_str() {
+        assert_eq!("too-low".parse::<Outcome>(), Ok(Outcome::TooLow));
+        assert_eq!(
+            "right".parse::<Outcome>(),
+            Err(OutcomeParseError(String::from("right")))
+        );
+    }
+
+    #[test]
+    fn test_submitter_submit() {
+        let (base_url, handle) = mock::serve(vec![Reply::new(
+            200,
+            "<article><p>That's the right answer!</p></article>",
+        )]);
+        let config = Config {
+            cache_dir: std::env::temp_dir().join(format!("aoc-submit-{}", std::process::id())),
+            min_interval: std::time::Duration::ZERO,
+            ..Config::default()
+        };
+        let submitter = Submitter {
+            client: Client::new(&config),
+            base_url,
+            year: 2022,
+            token: String::from("t0k3n"),
+        };
+
+        let result = submitter.submit(1, Part::Two, "45000\n").unwrap();
+        let request = &handle.join().unwrap()[0];
+        let _ = std::fs::remove_dir_all(&config.cache_dir);
+
+        assert_eq!(result, Outcome::Correct);
+        assert!(request.starts_with("POST /2022/day/1/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=t0k3n\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=45000"));
+    }
+
+    #[test]
+    fn test_submitter_submit_multi_line_err() {
+        let submitter = Submitter {
+            client: Client::new(&Config::default()),
+            base_url: String::from("http://127.0.0.1:9"),
+            year: 2022,
+            token: String::new(),
+        };
+        let result = submitter.submit(10, Part::Two, "##..\n..##\n");
+        assert!(matches!(result, Err(SubmitError::MultiLineAnswer)));
+    }
+}